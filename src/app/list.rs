@@ -10,7 +10,7 @@ use super::unicode;
 /// # KV
 /// Key-Value item for selectable, used in editable list
 /// ## UI
-/// ```
+/// ```text
 /// ${icon} ${key} » ${value}
 /// // such as
 /// ➤ name » John
@@ -97,8 +97,17 @@ pub fn run(cmd: Commands, terminal: &mut Option<DefaultTerminal>) -> Result<()>
             Commands::Create(create_args) => {
                 create_args.run(lang);
             }
-            Commands::Run => {
-                service::run::run(lang);
+            Commands::Run(run_args) => {
+                run_args.run(lang);
+            }
+            Commands::Build(build_args) => {
+                build_args.run(lang);
+            }
+            Commands::Snapshot(snapshot_args) => {
+                snapshot_args.run(lang);
+            }
+            Commands::Info(info_args) => {
+                info_args.run(lang);
             }
             Commands::Add { name } => {
                 service::add::run(&name);
@@ -17,7 +17,7 @@ use super::{unicode, AppComponent, BaseRunState, ComponentState};
 /// # Select
 /// single select component for terminal
 /// ## UI
-/// ```
+/// ```text
 /// ? ${title} [${placeholder}]
 ///   » ${options}
 ///        ...
@@ -282,7 +282,7 @@ impl<'c> Confirm<'c> {
 /// # Multi Select
 /// single select component for terminal
 /// ## UI
-/// ```
+/// ```text
 /// ? ${title} [${placeholder}]
 ///   » [ ] ${options}
 ///        ...
@@ -24,7 +24,11 @@ pub mod wasm;
 pub mod studio;
 
 use clap::Subcommand;
+use crate::service::build::BuildArgs;
 use crate::service::create::CreateArgs;
+use crate::service::info::InfoArgs;
+use crate::service::run::RunArgs;
+use crate::service::snapshot::SnapshotArgs;
 use crate::service::update::UpdateArgs;
 use crate::service::wasm::WasmArgs;
 
@@ -37,7 +41,13 @@ pub enum Commands {
     /// Install required tools and dependencies for development.  
     Install,
     /// Run **Makepad** or **GenUI** projects.
-    Run,
+    Run(RunArgs),
+    /// Compile **GenUI** projects once without watching. Use `--dry-run [--diff]` to check the compiled project is up to date
+    Build(BuildArgs),
+    /// Record or check snapshots of the generated output of **GenUI** projects under `snapshots/`
+    Snapshot(SnapshotArgs),
+    /// Show the effective compiler config of **GenUI** projects and where each value comes from (default, gen_ui.toml, `GENUI_*` env vars or flags)
+    Info(InfoArgs),
     /// Initialize or reset the CLI. Ract will generate: [.env, chain/env.toml, chain/]
     Init,
     /// Set or update environment variables and CLI configurations.  
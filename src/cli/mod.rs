@@ -2,6 +2,10 @@ pub mod command;
 use clap::Parser;
 use command::Commands;
 
+use crate::{app, entry::Env, log::LogItem, service};
+
+use crate::common::Result;
+
 #[derive(Parser)]
 #[command(
     name = "ract",
@@ -13,3 +17,51 @@ pub struct Cli {
     #[command(subcommand)]
     pub commands: Commands,
 }
+
+/// ## entry of the `ract` binary
+pub fn main() -> Result<()> {
+    // [check env] ------------------------------------------------------------------------------------------
+    if Env::check() {
+        // [check update] -----------------------------------------------------------------------------------
+        match service::update::check_auto_update() {
+            Ok(_) => {
+                return run();
+            }
+            Err(e) => {
+                LogItem::error(e.to_string()).print();
+            }
+        }
+    }
+
+    // [if not init] ----------------------------------------------------------------------------------------
+    if let Err(e) = service::init::run() {
+        LogItem::error(e.to_string()).print();
+        // exit
+        std::process::exit(1);
+    } else {
+        // continue to run
+        run()?;
+    }
+
+    Ok(())
+}
+
+fn run() -> Result<()> {
+    // [read from terminal] ---------------------------------------------------------------------
+    let cmd = Cli::parse().commands;
+    let mut terminal = if cmd.need_init() {
+        Some(ratatui::init())
+    } else {
+        None
+    };
+
+    let res = app::run(cmd, &mut terminal);
+    // [error handling] -------------------------------------------------------------------------
+    if let Err(e) = res {
+        LogItem::error(e.to_string()).print();
+        if let Some(terminal) = terminal.as_mut() {
+            app::destroy(terminal)?;
+        }
+    }
+    Ok(())
+}
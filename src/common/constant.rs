@@ -324,4 +324,97 @@ For more information, please see: [GenUI Official Documentation](https://privoce
 
 
 Thank you for your support, and we look forward to building a better GenUI with you!
-"#;
\ No newline at end of file
+"#;
+/// manifest of the builtin template (`template.toml`), see `Template`.
+/// `src` of each file is the name of the builtin content
+pub const BUILTIN_TEMPLATE: &str = r#"[template]
+name = "default"
+description = "Default template of ract"
+frameworks = ["gen_ui", "makepad"]
+
+# [gen_ui] ------------------------------------------------------------------------------
+[[dirs]]
+path = "resources"
+
+[[dirs]]
+path = "views"
+framework = "gen_ui"
+
+[[dirs]]
+path = "components"
+framework = "gen_ui"
+
+[[files]]
+path = "README.md"
+src = "gen_ui/README.md"
+framework = "gen_ui"
+scope = "workspace"
+
+[[files]]
+path = "src/main.rs"
+content = ""
+framework = "gen_ui"
+
+[[files]]
+path = "views/mod.gen"
+src = "gen_ui/views/mod.gen"
+framework = "gen_ui"
+
+[[files]]
+path = "views/root.gen"
+src = "gen_ui/views/root.gen"
+framework = "gen_ui"
+
+[[files]]
+path = "views/home.gen"
+src = "gen_ui/views/home.gen"
+framework = "gen_ui"
+
+[[files]]
+path = "components/mod.gen"
+src = "gen_ui/components/mod.gen"
+framework = "gen_ui"
+
+[[files]]
+path = "components/easy.gen"
+src = "gen_ui/components/easy.gen"
+framework = "gen_ui"
+
+[[files]]
+path = "components/hello.gen"
+src = "gen_ui/components/hello.gen"
+framework = "gen_ui"
+
+# [makepad] -----------------------------------------------------------------------------
+[[files]]
+path = "src/lib.rs"
+src = "makepad/src/lib.rs"
+framework = "makepad"
+
+[[files]]
+path = "src/main.rs"
+src = "makepad/src/main.rs"
+framework = "makepad"
+
+[[files]]
+path = "src/app.rs"
+src = "makepad/src/app.rs"
+framework = "makepad"
+"#;
+
+/// content of the file in [BUILTIN_TEMPLATE]
+pub fn builtin_template_file(src: &str) -> Option<&'static str> {
+    match src {
+        "gen_ui/README.md" => Some(GENUI_README),
+        "gen_ui/views/mod.gen" => Some(VIEW_MOD_GEN),
+        "gen_ui/views/root.gen" => Some(ROOT_GEN),
+        "gen_ui/views/home.gen" => Some(HOME_GEN),
+        "gen_ui/components/mod.gen" => Some(COMPONENT_MOD_GEN),
+        "gen_ui/components/easy.gen" => Some(EASY_GEN),
+        "gen_ui/components/hello.gen" => Some(HELLO_GEN),
+        "makepad/src/lib.rs" => Some(MAKEPAD_LIB_RS),
+        "makepad/src/main.rs" => Some(MAKEPAD_MAIN_RS),
+        "makepad/src/app.rs" => Some(MAKEPAD_APP_RS),
+        _ => None,
+    }
+}
@@ -4,7 +4,9 @@ use crate::log::LogExt;
 use crate::{entry::RactToml, log::CreateLogs};
 use gen_utils::common::{fs, ToToml};
 use gen_utils::error::Error;
+use std::io::ErrorKind;
 use std::path::Path;
+use std::sync::atomic::{AtomicUsize, Ordering};
 use std::{env::current_exe, path::PathBuf};
 use toml_edit::DocumentMut;
 
@@ -80,4 +82,44 @@ where
     }
 
     Ok(true)
-}
\ No newline at end of file
+}
+static TEMP_DIRS: AtomicUsize = AtomicUsize::new(0);
+
+/// # Temporary Dir
+/// a new empty dir in the temp dir of the system, it is removed with everything in it when dropped
+pub struct TempDir(PathBuf);
+
+impl TempDir {
+    /// ## create the dir named `<prefix>_<pid>_<n>`, which is not used by other processes or threads
+    pub fn new(prefix: &str) -> Result<Self, Error> {
+        loop {
+            let path = std::env::temp_dir().join(format!(
+                "{}_{}_{}",
+                prefix,
+                std::process::id(),
+                TEMP_DIRS.fetch_add(1, Ordering::Relaxed)
+            ));
+            match std::fs::create_dir(path.as_path()) {
+                Ok(_) => return Ok(Self(path)),
+                // left by a process which had the same pid
+                Err(e) if e.kind() == ErrorKind::AlreadyExists => continue,
+                Err(e) => {
+                    return Err(Error::from(format!(
+                        "can not create temp dir {}: {}",
+                        fs::path_to_str(&path),
+                        e
+                    )))
+                }
+            }
+        }
+    }
+    pub fn path(&self) -> &Path {
+        self.0.as_path()
+    }
+}
+
+impl Drop for TempDir {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_dir_all(self.0.as_path());
+    }
+}
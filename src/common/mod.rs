@@ -1,8 +1,10 @@
 mod constant;
 mod fs;
+mod toml;
 
 pub use fs::*;
 pub use constant::*;
+pub use toml::*;
 
 use crate::log::error::Error;
 
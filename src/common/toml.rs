@@ -0,0 +1,173 @@
+use toml_edit::{DocumentMut, Item, Table, Value};
+
+/// # Toml Origin
+/// the document read from a config file, and the document generated from the config when it is read.
+///
+/// configs which keep the origin write minimal edits back to the file (see [TomlOrigin::edit]), so comments,
+/// key order and keys which ract does not model are kept
+#[derive(Debug, Clone)]
+pub struct TomlOrigin {
+    pub doc: DocumentMut,
+    pub generated: DocumentMut,
+}
+
+impl TomlOrigin {
+    pub fn new(doc: DocumentMut, generated: DocumentMut) -> Self {
+        Self { doc, generated }
+    }
+    /// ## apply the changes of the config to the origin document
+    /// - current: the document generated from the config now
+    ///
+    /// only the keys whose generated values changed since the config is read are written
+    pub fn edit(&self, current: &DocumentMut) -> DocumentMut {
+        let mut doc = self.doc.clone();
+        edit_table(
+            doc.as_table_mut(),
+            self.generated.as_table(),
+            current.as_table(),
+        );
+        doc
+    }
+}
+
+fn edit_table(doc: &mut Table, before: &Table, after: &Table) {
+    // [removed] -------------------------------------------------------------------------------------------------
+    for (key, _) in before.iter() {
+        if !after.contains_key(key) {
+            doc.remove(key);
+        }
+    }
+    // [added or changed] ----------------------------------------------------------------------------------------
+    for (key, item) in after.iter() {
+        let old = before.get(key);
+        // unchanged keys are not written, even if they are not in the document (such as default values)
+        if old.is_some_and(|old| same(old, item)) {
+            continue;
+        }
+        match (doc.get_mut(key), item) {
+            (Some(Item::Table(doc)), Item::Table(after)) => {
+                let empty = Table::new();
+                let before = old.and_then(Item::as_table).unwrap_or(&empty);
+                edit_table(doc, before, after);
+            }
+            (Some(Item::Value(doc)), Item::Value(after)) => {
+                let before = old.and_then(Item::as_value);
+                edit_value(doc, before, after);
+            }
+            _ => {
+                doc.insert(key, item.clone());
+            }
+        }
+    }
+}
+
+/// replace the value and keep its decor, items appended to an array are formatted as the last item
+fn edit_value(doc: &mut Value, before: Option<&Value>, after: &Value) {
+    if let (Value::Array(doc), Some(Value::Array(before)), Value::Array(after)) =
+        (&mut *doc, before, after)
+    {
+        let appended = before.len() < after.len()
+            && doc.len() == before.len()
+            && before
+                .iter()
+                .zip(after.iter())
+                .all(|(before, after)| before.to_string().trim() == after.to_string().trim());
+        if appended {
+            let decor = doc.iter().last().map(|last| last.decor().clone());
+            // comment after the last item is kept after it
+            let trailing = doc.trailing().as_str().unwrap_or_default().to_string();
+            let comment = match trailing.rsplit_once('\n') {
+                Some((comment, indent)) if !comment.trim().is_empty() => {
+                    doc.set_trailing(format!("\n{}", indent));
+                    comment.to_string()
+                }
+                _ => String::new(),
+            };
+            for (index, value) in after.iter().skip(before.len()).enumerate() {
+                let mut value = value.clone();
+                if let Some(decor) = decor.as_ref() {
+                    *value.decor_mut() = decor.clone();
+                }
+                if index == 0 && !comment.is_empty() {
+                    let prefix = decor
+                        .as_ref()
+                        .and_then(|decor| decor.prefix())
+                        .and_then(|prefix| prefix.as_str())
+                        .unwrap_or(" ");
+                    value.decor_mut().set_prefix(format!("{}{}", comment, prefix));
+                }
+                doc.push_formatted(value);
+            }
+            return;
+        }
+    }
+    let decor = doc.decor().clone();
+    *doc = after.clone();
+    *doc.decor_mut() = decor;
+}
+
+fn same(a: &Item, b: &Item) -> bool {
+    a.to_string().trim() == b.to_string().trim()
+}
+
+#[cfg(test)]
+mod test_toml {
+    use toml_edit::{value, Array, DocumentMut};
+
+    use super::TomlOrigin;
+
+    #[test]
+    fn minimal_edit() {
+        let doc = r#"# workspace of the app
+target = "gen_ui" # do not change
+members = [
+    { src = "hello", target = "src_gen_0" }, # the first one
+]
+unknown = 1
+
+[plugins]
+# http plugin
+http = ".plugins/http"
+"#
+        .parse::<DocumentMut>()
+        .unwrap();
+        let generate = |members: &[&str], plugins: &[(&str, &str)]| {
+            let mut generated = DocumentMut::new();
+            generated.insert("target", value("gen_ui"));
+            let mut array = Array::new();
+            for member in members {
+                array.push(*member);
+            }
+            generated.insert("members", value(array));
+            let mut table = toml_edit::Table::new();
+            for (k, v) in plugins {
+                table.insert(k, value(*v));
+            }
+            generated.insert("plugins", toml_edit::Item::Table(table));
+            generated
+        };
+        // the generated document does not have to look like the file, only changes are compared
+        let origin = TomlOrigin::new(doc, generate(&["hello"], &[("http", "/abs/.plugins/http")]));
+        let edited = origin.edit(&generate(
+            &["hello", "world"],
+            &[("http", "/abs/.plugins/http"), ("ws", ".plugins/ws")],
+        ));
+
+        assert_eq!(
+            edited.to_string(),
+            r#"# workspace of the app
+target = "gen_ui" # do not change
+members = [
+    { src = "hello", target = "src_gen_0" }, # the first one
+    "world",
+]
+unknown = 1
+
+[plugins]
+# http plugin
+http = ".plugins/http"
+ws = ".plugins/ws"
+"#
+        );
+    }
+}
@@ -1,37 +1,200 @@
 use std::fmt::Display;
 
 use gen_utils::{err_from_to, error::Error};
-use toml_edit::{value, Item, Table};
+use toml_edit::{value, Item, Table, Value};
 
 use crate::{
-    entry::{compiler::excludes::Excludes, Underlayer},
+    entry::{
+        compiler::{excludes::Excludes, hooks::Hooks, service::DiagnosticFormat},
+        Underlayer,
+    },
     log::LogLevel,
 };
 
+use super::{
+    layer::{
+        parse_bool, ConfOverrides, Layer, Layers, ENV_EXCLUDES, ENV_LOGO, ENV_LOG_LEVEL,
+        ENV_WATCHER_POLL, ENV_WATCHER_POLL_INTERVAL,
+    },
+    output::{self, OutputConf},
+    WatcherConf,
+};
+
 /// Compiler Config
 /// ```toml
 /// [compiler]
 /// target = "makepad"
 /// logo = true
 /// log_level = "info"
+/// incremental = true
+/// threads = 0
+/// diagnostics = "human"
+/// use_gitignore = false
+///
+/// [compiler.hooks]
+/// after_all = "cargo fmt --manifest-path {compiled}/Cargo.toml"
+///
+/// [compiler.watcher]
+/// poll = false
+///
+/// [[compiler.outputs]]
+/// target = "dioxus"
+/// path = "src_gen_dioxus"
 /// ```
-#[derive(Debug)]
+/// `logo`, `log_level`, `excludes` and `watcher` can be overridden by `GENUI_*` env vars and CLI flags, see [Layer]
+#[derive(Debug, Clone)]
 pub struct CompilerConf {
-    pub target: Underlayer,
+    /// name of the underlayer compiler, builtin (see [Underlayer]), registered or external (see `registry`)
+    pub target: String,
     pub logo: bool,
     pub log_level: LogLevel,
+    /// gitignore-style patterns, see [Excludes]
     pub excludes: Excludes,
+    /// also exclude the files which are ignored by `.gitignore` in the project (and its workspace)
+    pub use_gitignore: bool,
+    /// keep the compiled project and the cache between runs, only changed files will be recompiled
+    /// - it only works when the cache is trusted (see `Cache::is_trusted`)
+    /// - set `false` to clear the cache and recreate the compiled project every time
+    pub incremental: bool,
+    /// number of workers used by the first compile (hash, copy and analyze files), `0` means auto
+    pub threads: usize,
+    /// format of compile diagnostics, see [DiagnosticFormat]
+    pub diagnostics: DiagnosticFormat,
+    /// commands run around the compilation, see [Hooks]
+    pub hooks: Hooks,
+    /// see [WatcherConf]
+    pub watcher: WatcherConf,
+    /// extra underlayers compiled from the same source, see [OutputConf]
+    pub outputs: Vec<OutputConf>,
+    /// where each value comes from
+    pub layers: Layers,
 }
 
 impl Default for CompilerConf {
     fn default() -> Self {
         Self {
-            target: Default::default(),
+            target: Underlayer::default().name().to_string(),
             logo: true,
             log_level: Default::default(),
             excludes: Default::default(),
+            use_gitignore: false,
+            incremental: true,
+            threads: 0,
+            diagnostics: Default::default(),
+            hooks: Default::default(),
+            watcher: Default::default(),
+            outputs: vec![],
+            layers: Default::default(),
+        }
+    }
+}
+
+impl CompilerConf {
+    /// ## override the settings by `GENUI_*` env vars
+    /// - var: get the value of the env var, such as `|key| std::env::var(key).ok()`
+    pub fn apply_env<F>(&mut self, var: F) -> Result<(), Error>
+    where
+        F: Fn(&str) -> Option<String>,
+    {
+        if let Some(logo) = var(ENV_LOGO) {
+            self.logo = parse_bool(ENV_LOGO, &logo)?;
+            self.layers.set("logo", Layer::Env(ENV_LOGO));
+        }
+        if let Some(log_level) = var(ENV_LOG_LEVEL) {
+            self.log_level = log_level.trim().parse()?;
+            self.layers.set("log_level", Layer::Env(ENV_LOG_LEVEL));
+        }
+        if let Some(excludes) = var(ENV_EXCLUDES) {
+            self.add_excludes(
+                excludes.split(',').map(str::trim).filter(|s| !s.is_empty()),
+                Layer::Env(ENV_EXCLUDES),
+            );
+        }
+        if let Some(poll) = var(ENV_WATCHER_POLL) {
+            self.watcher.poll = parse_bool(ENV_WATCHER_POLL, &poll)?;
+            self.layers
+                .set("watcher.poll", Layer::Env(ENV_WATCHER_POLL));
+        }
+        if let Some(interval) = var(ENV_WATCHER_POLL_INTERVAL) {
+            self.watcher.poll_interval = interval.trim().parse().map_err(|_| {
+                Error::from(format!(
+                    "{} should be milliseconds, got `{}`",
+                    ENV_WATCHER_POLL_INTERVAL, interval
+                ))
+            })?;
+            self.layers.set(
+                "watcher.poll_interval",
+                Layer::Env(ENV_WATCHER_POLL_INTERVAL),
+            );
+        }
+        Ok(())
+    }
+    /// ## override the settings by CLI flags
+    pub fn apply_cli(&mut self, overrides: &ConfOverrides) -> Result<(), Error> {
+        if let Some(log_level) = overrides.log_level.as_ref() {
+            self.log_level = log_level.parse()?;
+            self.layers.set("log_level", Layer::Cli("--log-level"));
+        }
+        if overrides.no_logo {
+            self.logo = false;
+            self.layers.set("logo", Layer::Cli("--no-logo"));
+        }
+        self.add_excludes(
+            overrides.excludes.iter().map(String::as_str),
+            Layer::Cli("--exclude"),
+        );
+        if overrides.poll {
+            self.watcher.poll = true;
+            self.layers.set("watcher.poll", Layer::Cli("--poll"));
+        }
+        if let Some(interval) = overrides.poll_interval {
+            self.watcher.poll_interval = interval;
+            self.layers
+                .set("watcher.poll_interval", Layer::Cli("--poll-interval"));
+        }
+        Ok(())
+    }
+    fn add_excludes<'a, I>(&mut self, patterns: I, layer: Layer)
+    where
+        I: Iterator<Item = &'a str>,
+    {
+        for pattern in patterns {
+            if !self.excludes.0.iter().any(|exclude| exclude == pattern) {
+                self.excludes.0.push(pattern.to_string());
+            }
+            self.layers.add("excludes", layer);
         }
     }
+    /// ## effective settings which can be layered, and where each value comes from (`ract info`)
+    pub fn layered(&self) -> Vec<(&'static str, String, &[Layer])> {
+        let excludes = format!(
+            "[{}]",
+            self.excludes
+                .0
+                .iter()
+                .map(|pattern| format!("{:?}", pattern))
+                .collect::<Vec<String>>()
+                .join(", ")
+        );
+        [
+            ("target", format!("{:?}", self.target)),
+            ("logo", self.logo.to_string()),
+            ("log_level", self.log_level.to_string()),
+            ("excludes", excludes),
+            ("use_gitignore", self.use_gitignore.to_string()),
+            ("incremental", self.incremental.to_string()),
+            ("threads", self.threads.to_string()),
+            ("diagnostics", Value::from(&self.diagnostics).to_string()),
+            ("watcher.poll", self.watcher.poll.to_string()),
+            (
+                "watcher.poll_interval",
+                self.watcher.poll_interval.to_string(),
+            ),
+        ]
+        .into_iter()
+        .map(|(key, value)| (key, value, self.layers.get(key)))
+        .collect()
+    }
 }
 
 impl TryFrom<&Item> for CompilerConf {
@@ -39,14 +202,18 @@ impl TryFrom<&Item> for CompilerConf {
 
     fn try_from(value: &Item) -> Result<Self, Self::Error> {
         if let Some(table) = value.as_table() {
-            let target = table
-                .get("target")
-                .and_then(|item| item.as_str())
-                .map_or_else(|| Ok(Default::default()), |s| s.parse())?;
+            let target = match table.get("target") {
+                Some(item) => item
+                    .as_str()
+                    .filter(|s| !s.trim().is_empty())
+                    .map(|s| s.trim().to_string())
+                    .ok_or_else(|| Error::from("[compiler] target must be a non-empty string"))?,
+                None => Underlayer::default().name().to_string(),
+            };
             let logo = table
                 .get("logo")
                 .and_then(|item| item.as_bool())
-                .unwrap_or_default();
+                .unwrap_or(true);
             let log_level = table
                 .get("log_level")
                 .and_then(|item| item.as_str())
@@ -57,11 +224,83 @@ impl TryFrom<&Item> for CompilerConf {
                 .and_then(|item| item.as_array())
                 .map_or_else(|| Ok(Default::default()), |array| array.try_into())?;
 
+            let use_gitignore = table
+                .get("use_gitignore")
+                .and_then(|item| item.as_bool())
+                .unwrap_or_default();
+
+            let incremental = table
+                .get("incremental")
+                .and_then(|item| item.as_bool())
+                .unwrap_or(true);
+
+            let threads = table
+                .get("threads")
+                .and_then(|item| item.as_integer())
+                .map_or_else(
+                    || Ok(0),
+                    |threads| {
+                        usize::try_from(threads)
+                            .map_err(|_| Error::from("[compiler] threads must be a non-negative integer"))
+                    },
+                )?;
+
+            let diagnostics = table
+                .get("diagnostics")
+                .and_then(|item| item.as_str())
+                .map_or_else(|| Ok(Default::default()), |s| s.parse())?;
+
+            let hooks = table
+                .get("hooks")
+                .map_or_else(|| Ok(Default::default()), |item| item.try_into())?;
+
+            let watcher: WatcherConf = table
+                .get("watcher")
+                .map_or_else(|| Ok(Default::default()), |item| item.try_into())?;
+
+            let outputs = table
+                .get("outputs")
+                .map_or_else(|| Ok(vec![]), OutputConf::read)?;
+
+            let mut layers = Layers::default();
+            for key in [
+                "target",
+                "logo",
+                "log_level",
+                "excludes",
+                "use_gitignore",
+                "incremental",
+                "threads",
+                "diagnostics",
+            ] {
+                if table.contains_key(key) {
+                    layers.set(key, Layer::Toml);
+                }
+            }
+            if let Some(watcher) = table.get("watcher").and_then(|item| item.as_table()) {
+                for (key, layer) in [
+                    ("poll", "watcher.poll"),
+                    ("poll_interval", "watcher.poll_interval"),
+                ] {
+                    if watcher.contains_key(key) {
+                        layers.set(layer, Layer::Toml);
+                    }
+                }
+            }
+
             return Ok(Self {
                 target,
                 logo,
                 log_level,
                 excludes,
+                use_gitignore,
+                incremental,
+                threads,
+                diagnostics,
+                hooks,
+                watcher,
+                outputs,
+                layers,
             });
         }
 
@@ -78,10 +317,69 @@ impl Display for CompilerConf {
 impl From<&CompilerConf> for Item {
     fn from(conf: &CompilerConf) -> Self {
         let mut table = Table::new();
-        table.insert("target", Item::Value((&conf.target).into()));
+        table.insert("target", value(conf.target.as_str()));
         table.insert("logo", value(conf.logo));
         table.insert("log_level", Item::Value((&conf.log_level).into()));
         table.insert("excludes", Item::Value((&conf.excludes).into()));
+        table.insert("use_gitignore", value(conf.use_gitignore));
+        table.insert("incremental", value(conf.incremental));
+        table.insert("threads", value(conf.threads as i64));
+        table.insert("diagnostics", Item::Value((&conf.diagnostics).into()));
+        if !conf.hooks.is_empty() {
+            table.insert("hooks", (&conf.hooks).into());
+        }
+        if !conf.watcher.is_default() {
+            table.insert("watcher", (&conf.watcher).into());
+        }
+        if !conf.outputs.is_empty() {
+            table.insert("outputs", output::to_item(&conf.outputs));
+        }
         Item::Table(table)
     }
 }
+
+#[cfg(test)]
+mod test_layers {
+    use toml_edit::DocumentMut;
+
+    use super::CompilerConf;
+    use crate::entry::compiler::conf::{ConfOverrides, Layer};
+
+    #[test]
+    fn layered() {
+        let doc = "[compiler]\nlogo = false\nlog_level = \"warn\"\nexcludes = [\"target\"]\n"
+            .parse::<DocumentMut>()
+            .unwrap();
+        let mut conf = CompilerConf::try_from(doc.get("compiler").unwrap()).unwrap();
+        conf.apply_env(|key| match key {
+            "GENUI_LOG_LEVEL" => Some("debug".to_string()),
+            "GENUI_EXCLUDES" => Some("*.bak, target".to_string()),
+            _ => None,
+        })
+        .unwrap();
+        conf.apply_cli(&ConfOverrides {
+            log_level: Some("error".to_string()),
+            excludes: vec!["tmp".to_string()],
+            ..Default::default()
+        })
+        .unwrap();
+
+        assert!(!conf.logo);
+        assert_eq!(conf.layers.get("logo"), [Layer::Toml]);
+        assert_eq!(conf.log_level.to_string(), "\"error\"");
+        assert_eq!(conf.layers.get("log_level"), [Layer::Cli("--log-level")]);
+        assert_eq!(conf.excludes.0, ["target", "*.bak", "tmp"]);
+        assert_eq!(
+            conf.layers.get("excludes"),
+            [
+                Layer::Toml,
+                Layer::Env("GENUI_EXCLUDES"),
+                Layer::Cli("--exclude")
+            ]
+        );
+        assert_eq!(conf.layers.get("watcher.poll"), [Layer::Default]);
+        assert!(conf
+            .apply_env(|key| (key == "GENUI_LOGO").then(|| "maybe".to_string()))
+            .is_err());
+    }
+}
@@ -0,0 +1,92 @@
+use std::{collections::BTreeMap, fmt::Display};
+
+use clap::Args;
+
+/// `GENUI_LOGO`: `true` or `false` (`1` or `0`)
+pub const ENV_LOGO: &str = "GENUI_LOGO";
+/// `GENUI_LOG_LEVEL`: such as `debug`, see `LogLevel`
+pub const ENV_LOG_LEVEL: &str = "GENUI_LOG_LEVEL";
+/// `GENUI_EXCLUDES`: patterns separated by `,` which are added to `[compiler] excludes`
+pub const ENV_EXCLUDES: &str = "GENUI_EXCLUDES";
+/// `GENUI_WATCHER_POLL`: `true` or `false` (`1` or `0`)
+pub const ENV_WATCHER_POLL: &str = "GENUI_WATCHER_POLL";
+/// `GENUI_WATCHER_POLL_INTERVAL`: milliseconds
+pub const ENV_WATCHER_POLL_INTERVAL: &str = "GENUI_WATCHER_POLL_INTERVAL";
+
+/// # Config Layer
+/// where the effective value of a compiler setting comes from, later layers override earlier ones:
+///
+/// defaults < gen_ui.toml < `GENUI_*` environment variables < CLI flags
+///
+/// `excludes` is the only setting which is extended instead of overridden by env vars and CLI flags
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Layer {
+    Default,
+    Toml,
+    Env(&'static str),
+    Cli(&'static str),
+}
+
+impl Display for Layer {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Layer::Default => f.write_str("default"),
+            Layer::Toml => f.write_str("gen_ui.toml"),
+            Layer::Env(var) => write!(f, "env {}", var),
+            Layer::Cli(flag) => f.write_str(flag),
+        }
+    }
+}
+
+/// # Config Layers
+/// layers of each setting in `[compiler]`, settings which are not recorded come from [Layer::Default]
+#[derive(Debug, Clone, Default)]
+pub struct Layers(BTreeMap<&'static str, Vec<Layer>>);
+
+impl Layers {
+    /// the value is overridden by the layer
+    pub fn set(&mut self, key: &'static str, layer: Layer) {
+        self.0.insert(key, vec![layer]);
+    }
+    /// the value is extended by the layer
+    pub fn add(&mut self, key: &'static str, layer: Layer) {
+        let layers = self.0.entry(key).or_insert_with(|| vec![Layer::Default]);
+        if !layers.contains(&layer) {
+            layers.push(layer);
+        }
+    }
+    /// layers which make up the effective value
+    pub fn get(&self, key: &str) -> &[Layer] {
+        self.0.get(key).map_or(&[Layer::Default], Vec::as_slice)
+    }
+}
+
+/// # Config Overrides
+/// CLI flags which override the compiler settings in gen_ui.toml and `GENUI_*` env vars (the top layer)
+#[derive(Args, Debug, Clone, Default)]
+pub struct ConfOverrides {
+    /// Log level of the compiler: [trace, debug, info, warn, error, off]
+    #[arg(long, value_name = "LEVEL")]
+    pub log_level: Option<String>,
+    /// Do not print the logo of the compiler
+    #[arg(long, default_value = "false")]
+    pub no_logo: bool,
+    /// Exclude more files from compiling (gitignore-style pattern, can be repeated)
+    #[arg(long = "exclude", value_name = "PATTERN")]
+    pub excludes: Vec<String>,
+    /// Watch files by polling, for network file systems or containers
+    #[arg(long, default_value = "false")]
+    pub poll: bool,
+    /// Interval of the polling watcher in milliseconds
+    #[arg(long, value_name = "MS")]
+    pub poll_interval: Option<u64>,
+}
+
+/// ## parse a bool in env vars
+pub(crate) fn parse_bool(var: &str, value: &str) -> Result<bool, String> {
+    match value.trim().to_lowercase().as_str() {
+        "true" | "1" => Ok(true),
+        "false" | "0" => Ok(false),
+        _ => Err(format!("{} should be true or false, got `{}`", var, value)),
+    }
+}
@@ -1,31 +1,50 @@
 mod compiler;
+mod layer;
+mod output;
+mod validate;
+mod watcher;
 
 pub use compiler::CompilerConf;
-use std::{collections::HashMap, fmt::Display, path::PathBuf};
+pub use layer::*;
+pub use output::OutputConf;
+pub use watcher::WatcherConf;
+pub use validate::{validate, ConfWarning};
+use std::{
+    collections::HashMap,
+    fmt::Display,
+    path::{Path, PathBuf},
+};
 
 use gen_utils::{
     common::{fs, ToToml},
+    compiler::UnderlayerConfImpl,
     error::{ConvertError, Error},
 };
 
 use toml_edit::{DocumentMut, Item, Table};
 
+use crate::common::TomlOrigin;
+
 use super::{target::CompileUnderlayer, Underlayer};
 
 /// Compiler Config for gen_ui.toml
 /// ```toml
 /// [compiler]
-/// // see [Underlayer]
+/// // see [CompilerConf], `target` selects the underlayer
 /// [makepad]
-/// // see [MakepadConfig]
+/// // see [MakepadConfig], or `[<target>]` for other underlayers (see `registry::CustomConf`)
 /// ```
 #[derive(Debug)]
 pub struct Conf {
     pub compiler: CompilerConf,
-    /// underlayer for makepad (current support)
+    /// config of the target underlayer
     pub underlayer: CompileUnderlayer,
     /// genui plugins, each plugin has a token.toml file
     pub plugins: Option<HashMap<String, PathBuf>>,
+    /// problems found when reading gen_ui.toml, see [validate]
+    pub warnings: Vec<ConfWarning>,
+    /// gen_ui.toml as it is read, `write` only edits the changed keys of it
+    origin: Option<TomlOrigin>,
 }
 
 impl Conf {
@@ -44,9 +63,57 @@ impl Conf {
 
         self.plugins.as_mut().unwrap().insert(name, path);
     }
+    /// ## conf of an extra output (`[[compiler.outputs]]`)
+    /// `[compiler]` and `[plugins]` are the same as the conf, the target is the underlayer of the output
+    pub fn output(&self, output: &OutputConf, underlayer: Box<dyn UnderlayerConfImpl>) -> Self {
+        let compiler = CompilerConf {
+            target: output.target.to_string(),
+            outputs: vec![],
+            ..self.compiler.clone()
+        };
+
+        Self {
+            compiler,
+            underlayer: CompileUnderlayer {
+                target: underlayer,
+                others: None,
+            },
+            plugins: self.plugins.clone(),
+            warnings: vec![],
+            origin: None,
+        }
+    }
+    /// ## turn the warnings into an error (`--strict`)
+    pub fn strict(&self) -> Result<(), Error> {
+        if self.warnings.is_empty() {
+            return Ok(());
+        }
+        Err(Error::from(format!(
+            "gen_ui.toml has {} problem(s) in strict mode:\n{}",
+            self.warnings.len(),
+            self.warnings
+                .iter()
+                .map(|warning| format!("  - {}", warning))
+                .collect::<Vec<String>>()
+                .join("\n")
+        )))
+    }
 }
 
 impl ToToml for Conf {
+    /// ## write gen_ui.toml
+    /// a conf read from the file only writes the keys which are changed since then, comments, key order and
+    /// unknown keys in the file are kept. otherwise the whole file is generated
+    fn write<P>(&self, path: P) -> Result<(), Error>
+    where
+        P: AsRef<Path>,
+    {
+        match self.origin.as_ref() {
+            Some(origin) => fs::write(path, &origin.edit(&self.to_toml()).to_string()),
+            None => fs::write(path, &self.to_string()),
+        }
+    }
+
     fn to_toml(&self) -> DocumentMut {
         let mut table = Table::new();
 
@@ -56,6 +123,16 @@ impl ToToml for Conf {
             let (k, v) = underlayer.into_iter().next().unwrap();
             table.insert(&k, v);
         }
+        // outputs which use the same underlayer share the table
+        for other in self.underlayer.others.iter().flatten() {
+            if let Item::Table(underlayer) = other.to_item() {
+                for (k, v) in underlayer.into_iter() {
+                    if !table.contains_key(&k) {
+                        table.insert(&k, v);
+                    }
+                }
+            }
+        }
 
         let plugins = if let Some(plugins) = self.plugins.as_ref() {
             let mut table = Table::new();
@@ -79,12 +156,17 @@ impl TryFrom<(PathBuf, Underlayer)> for Conf {
     type Error = Error;
 
     fn try_from(value: (PathBuf, Underlayer)) -> Result<Self, Self::Error> {
-        let compiler = CompilerConf::default();
+        let compiler = CompilerConf {
+            target: value.1.name().to_string(),
+            ..Default::default()
+        };
 
         Ok(Self {
             compiler,
             underlayer: value.try_into()?,
             plugins: None,
+            warnings: vec![],
+            origin: None,
         })
     }
 }
@@ -144,13 +226,21 @@ impl TryFrom<(String, PathBuf)> for Conf {
         } else {
             None
         };
+        // [validate] -------------------------------------------------------------------------------------------------
+        let warnings = validate(&toml, path.as_path(), compiler.target.as_str());
         // [underlayer] -----------------------------------------------------------------------------------------------
-        let underlayer = CompileUnderlayer::try_from((toml, compiler.target))?;
+        let origin = toml.clone();
+        let underlayer = CompileUnderlayer::try_from((toml.clone(), compiler.target.as_str()))?
+            .with_others(toml, &compiler.outputs)?;
 
-        Ok(Self {
+        let mut conf = Self {
             compiler,
             underlayer,
             plugins,
-        })
+            warnings,
+            origin: None,
+        };
+        conf.origin = Some(TomlOrigin::new(origin, conf.to_toml()));
+        Ok(conf)
     }
 }
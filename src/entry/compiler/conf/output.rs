@@ -0,0 +1,122 @@
+use std::path::PathBuf;
+
+use gen_utils::{common::fs, error::Error};
+use toml_edit::{value, ArrayOfTables, Item, Table};
+
+/// # Output Config
+/// an extra underlayer which compiles the same GenUI project into another crate of the workspace.
+/// the config of the underlayer is the `[<target>]` table, the same as `[compiler] target`
+/// ```toml
+/// [[compiler.outputs]]
+/// target = "dioxus"
+/// # path of the compiled project, relative to the workspace
+/// path = "src_gen_dioxus"
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OutputConf {
+    /// name of the underlayer compiler, see `registry::compiler`
+    pub target: String,
+    pub path: PathBuf,
+}
+
+impl OutputConf {
+    /// ## read `[[compiler.outputs]]`
+    /// each output should have its own path
+    pub fn read(item: &Item) -> Result<Vec<Self>, Error> {
+        let outputs = item
+            .as_array_of_tables()
+            .ok_or_else(|| Error::from("[[compiler.outputs]] must be an array of tables"))?
+            .iter()
+            .map(Self::try_from)
+            .collect::<Result<Vec<Self>, Error>>()?;
+
+        for (index, output) in outputs.iter().enumerate() {
+            if outputs[..index].iter().any(|o| o.path == output.path) {
+                return Err(Error::from(format!(
+                    "[[compiler.outputs]] path `{}` is used by more than one output",
+                    fs::path_to_str(&output.path)
+                )));
+            }
+        }
+        Ok(outputs)
+    }
+}
+
+impl TryFrom<&Table> for OutputConf {
+    type Error = Error;
+
+    fn try_from(table: &Table) -> Result<Self, Self::Error> {
+        let get = |key: &str| {
+            table
+                .get(key)
+                .and_then(Item::as_str)
+                .map(str::trim)
+                .filter(|s| !s.is_empty())
+                .ok_or_else(|| {
+                    Error::from(format!(
+                        "[[compiler.outputs]] {} must be a non-empty string",
+                        key
+                    ))
+                })
+        };
+
+        Ok(Self {
+            target: get("target")?.to_string(),
+            path: PathBuf::from(get("path")?),
+        })
+    }
+}
+
+impl From<&OutputConf> for Table {
+    fn from(conf: &OutputConf) -> Self {
+        let mut table = Table::new();
+        table.insert("target", value(conf.target.as_str()));
+        table.insert("path", value(fs::path_to_str(&conf.path)));
+        table
+    }
+}
+
+/// outputs are written as `[[compiler.outputs]]`
+pub fn to_item(outputs: &[OutputConf]) -> Item {
+    Item::ArrayOfTables(outputs.iter().map(Table::from).collect::<ArrayOfTables>())
+}
+
+#[cfg(test)]
+mod test_output {
+    use std::path::PathBuf;
+
+    use toml_edit::DocumentMut;
+
+    use super::{to_item, OutputConf};
+
+    #[test]
+    fn outputs() {
+        let doc = "[compiler]\n\n[[compiler.outputs]]\ntarget = \"dioxus\"\npath = \"src_gen_dioxus\"\n\n[[compiler.outputs]]\ntarget = \"makepad\"\npath = \"mobile/src_gen\"\n"
+            .parse::<DocumentMut>()
+            .unwrap();
+        let outputs = OutputConf::read(&doc["compiler"]["outputs"]).unwrap();
+        assert_eq!(
+            outputs,
+            [
+                OutputConf {
+                    target: "dioxus".to_string(),
+                    path: PathBuf::from("src_gen_dioxus"),
+                },
+                OutputConf {
+                    target: "makepad".to_string(),
+                    path: PathBuf::from("mobile/src_gen"),
+                },
+            ]
+        );
+        assert_eq!(OutputConf::read(&to_item(&outputs)).unwrap(), outputs);
+
+        let same = "[[outputs]]\ntarget = \"a\"\npath = \"out\"\n[[outputs]]\ntarget = \"b\"\npath = \"out\"\n"
+            .parse::<DocumentMut>()
+            .unwrap();
+        assert!(OutputConf::read(&same["outputs"]).is_err());
+        let missing = "[[outputs]]\ntarget = \"a\"\n"
+            .parse::<DocumentMut>()
+            .unwrap();
+        assert!(OutputConf::read(&missing["outputs"]).is_err());
+    }
+}
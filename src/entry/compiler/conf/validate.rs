@@ -0,0 +1,315 @@
+use std::{fmt::Display, path::Path};
+
+use gen_utils::common::fs;
+use toml_edit::{DocumentMut, Item, Table};
+
+use crate::entry::Underlayer;
+
+/// keys of `[compiler]` and the expected type of each value
+const COMPILER_KEYS: [(&str, &str); 11] = [
+    ("target", "string"),
+    ("logo", "bool"),
+    ("log_level", "string"),
+    ("excludes", "array"),
+    ("use_gitignore", "bool"),
+    ("incremental", "bool"),
+    ("threads", "integer"),
+    ("diagnostics", "string"),
+    ("hooks", "table"),
+    ("watcher", "table"),
+    ("outputs", "array of tables"),
+];
+const HOOKS_KEYS: [&str; 3] = ["before_all", "after_all", "after_file"];
+const WATCHER_KEYS: [&str; 2] = ["poll", "poll_interval"];
+const OUTPUT_KEYS: [&str; 2] = ["target", "path"];
+/// `routers` is written by `makepad_gen_plugin`, `router` is read
+const MAKEPAD_KEYS: [&str; 6] = ["entry", "root", "dependencies", "wasm", "router", "routers"];
+const MAKEPAD_WASM_KEYS: [&str; 2] = ["fresh", "port"];
+/// file which each plugin dir must contain
+const PLUGIN_TOKEN: &str = "token.toml";
+
+/// # Config Warning
+/// a problem in gen_ui.toml which is ignored by the compiler (or fails later), see [validate]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ConfWarning {
+    /// key or table which is not used by ract, with the closest known key
+    UnknownKey {
+        table: String,
+        key: String,
+        suggestion: Option<String>,
+    },
+    /// value of a known key has the wrong type, it falls back to the default value
+    InvalidType {
+        table: String,
+        key: String,
+        expected: &'static str,
+    },
+    /// plugin dir does not exist or has no token file
+    Plugin { name: String, reason: String },
+}
+
+impl Display for ConfWarning {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ConfWarning::UnknownKey {
+                table,
+                key,
+                suggestion,
+            } => {
+                let key = if table.is_empty() {
+                    format!("[{}]", key)
+                } else {
+                    format!("`{}` in [{}]", key, table)
+                };
+                match suggestion {
+                    Some(suggestion) => {
+                        write!(f, "unknown {}, did you mean `{}`?", key, suggestion)
+                    }
+                    None => write!(f, "unknown {}", key),
+                }
+            }
+            ConfWarning::InvalidType {
+                table,
+                key,
+                expected,
+            } => write!(
+                f,
+                "`{}` in [{}] should be {}, the default value is used",
+                key, table, expected
+            ),
+            ConfWarning::Plugin { name, reason } => {
+                write!(f, "plugin `{}` in [plugins] {}", name, reason)
+            }
+        }
+    }
+}
+
+/// ## validate gen_ui.toml
+/// check the keys of `[compiler]`, `[compiler.hooks]`, `[[compiler.outputs]]`, the builtin underlayer tables and
+/// the top level tables, and that each plugin in `[plugins]` is a dir which contains `token.toml`.
+/// keys of registered and external underlayers are passed to the compiler as they are, so they are not checked
+///
+/// - path: the GenUI project, plugin paths are relative to it
+/// - target: `[compiler] target`
+pub fn validate(toml: &DocumentMut, path: &Path, target: &str) -> Vec<ConfWarning> {
+    let mut warnings = vec![];
+
+    // underlayers of `[compiler] target` and `[[compiler.outputs]]`
+    let mut targets = vec![target];
+    if let Some(outputs) = toml
+        .get("compiler")
+        .and_then(|compiler| compiler.get("outputs"))
+        .and_then(Item::as_array_of_tables)
+    {
+        targets.extend(outputs.iter().filter_map(|output| output.get("target")?.as_str()));
+    }
+    targets.sort();
+    targets.dedup();
+    // [top level] -----------------------------------------------------------------------------------------------
+    let tables = [&["compiler", "plugins"], targets.as_slice()].concat();
+    unknown_keys(toml.as_table(), "", &tables, &mut warnings);
+    // [compiler] ------------------------------------------------------------------------------------------------
+    if let Some(compiler) = toml.get("compiler").and_then(Item::as_table) {
+        let keys = COMPILER_KEYS.map(|(key, _)| key);
+        unknown_keys(compiler, "compiler", &keys, &mut warnings);
+        for (key, expected) in COMPILER_KEYS {
+            let valid = match compiler.get(key) {
+                None => true,
+                Some(item) => match expected {
+                    "string" => item.is_str(),
+                    "bool" => item.is_bool(),
+                    "array" => item.is_array(),
+                    "integer" => item.is_integer(),
+                    "array of tables" => item.is_array_of_tables(),
+                    _ => item.is_table_like(),
+                },
+            };
+            if !valid {
+                warnings.push(ConfWarning::InvalidType {
+                    table: "compiler".to_string(),
+                    key: key.to_string(),
+                    expected,
+                });
+            }
+        }
+        if let Some(hooks) = compiler.get("hooks").and_then(Item::as_table) {
+            unknown_keys(hooks, "compiler.hooks", &HOOKS_KEYS, &mut warnings);
+        }
+        if let Some(watcher) = compiler.get("watcher").and_then(Item::as_table) {
+            unknown_keys(watcher, "compiler.watcher", &WATCHER_KEYS, &mut warnings);
+        }
+        if let Some(outputs) = compiler.get("outputs").and_then(Item::as_array_of_tables) {
+            for output in outputs.iter() {
+                unknown_keys(output, "compiler.outputs", &OUTPUT_KEYS, &mut warnings);
+            }
+        }
+    }
+    // [underlayer] ----------------------------------------------------------------------------------------------
+    for underlayer in targets.iter().filter_map(|target| target.parse::<Underlayer>().ok()) {
+        match underlayer {
+            Underlayer::Makepad => {
+                if let Some(makepad) = toml.get("makepad").and_then(Item::as_table) {
+                    unknown_keys(makepad, "makepad", &MAKEPAD_KEYS, &mut warnings);
+                    if let Some(wasm) = makepad.get("wasm").and_then(Item::as_table) {
+                        unknown_keys(wasm, "makepad.wasm", &MAKEPAD_WASM_KEYS, &mut warnings);
+                    }
+                }
+            }
+        }
+    }
+    // [plugins] -------------------------------------------------------------------------------------------------
+    if let Some(plugins) = toml.get("plugins").and_then(Item::as_table) {
+        for (name, item) in plugins.iter() {
+            let reason = match item.as_str() {
+                None => Some("should be the path of the plugin dir".to_string()),
+                Some(plugin) => {
+                    let plugin = fs::relative_with_prefix(path, plugin);
+                    if !plugin.is_dir() {
+                        Some(format!("is not a dir: {}", plugin.display()))
+                    } else if !plugin.join(PLUGIN_TOKEN).is_file() {
+                        Some(format!(
+                            "has no {}: {}",
+                            PLUGIN_TOKEN,
+                            plugin.join(PLUGIN_TOKEN).display()
+                        ))
+                    } else {
+                        None
+                    }
+                }
+            };
+            if let Some(reason) = reason {
+                warnings.push(ConfWarning::Plugin {
+                    name: name.to_string(),
+                    reason,
+                });
+            }
+        }
+    }
+
+    warnings
+}
+
+fn unknown_keys(table: &Table, name: &str, keys: &[&str], warnings: &mut Vec<ConfWarning>) {
+    for (key, _) in table.iter() {
+        if !keys.contains(&key) {
+            warnings.push(ConfWarning::UnknownKey {
+                table: name.to_string(),
+                key: key.to_string(),
+                suggestion: suggest(key, keys).map(ToString::to_string),
+            });
+        }
+    }
+}
+
+/// ## the closest key which is a possible typo of the input
+/// edit distance should be at most a third of the input (at least 1)
+fn suggest<'a>(input: &str, keys: &[&'a str]) -> Option<&'a str> {
+    let max = (input.chars().count() / 3).max(1);
+    keys.iter()
+        .map(|key| (*key, distance(input, key)))
+        .filter(|(_, distance)| *distance <= max)
+        .min_by_key(|(_, distance)| *distance)
+        .map(|(key, _)| key)
+}
+
+/// edit distance, swapping two adjacent chars counts as one edit (optimal string alignment)
+fn distance(a: &str, b: &str) -> usize {
+    let (a, b) = (
+        a.chars().collect::<Vec<char>>(),
+        b.chars().collect::<Vec<char>>(),
+    );
+    let mut d = vec![vec![0; b.len() + 1]; a.len() + 1];
+    for (i, row) in d.iter_mut().enumerate() {
+        row[0] = i;
+    }
+    for (j, cell) in d[0].iter_mut().enumerate() {
+        *cell = j;
+    }
+    for i in 1..=a.len() {
+        for j in 1..=b.len() {
+            let cost = usize::from(a[i - 1] != b[j - 1]);
+            d[i][j] = (d[i - 1][j - 1] + cost)
+                .min(d[i - 1][j] + 1)
+                .min(d[i][j - 1] + 1);
+            if i > 1 && j > 1 && a[i - 1] == b[j - 2] && a[i - 2] == b[j - 1] {
+                d[i][j] = d[i][j].min(d[i - 2][j - 2] + 1);
+            }
+        }
+    }
+    d[a.len()][b.len()]
+}
+
+#[cfg(test)]
+mod test_validate {
+    use toml_edit::DocumentMut;
+
+    use super::{validate, ConfWarning};
+    use crate::common::TempDir;
+
+    #[test]
+    fn typos() {
+        let tmp = TempDir::new("ract_validate").unwrap();
+        let dir = tmp.path();
+        std::fs::create_dir_all(dir.join(".plugins/http")).unwrap();
+        std::fs::write(dir.join(".plugins/http/token.toml"), "").unwrap();
+        std::fs::create_dir_all(dir.join(".plugins/empty")).unwrap();
+
+        let toml = r#"
+[compiler]
+target = "makepad"
+log_lvl = "debug"
+logo = "yes"
+exclude = ["target"]
+
+[compiler.hooks]
+after = "cargo fmt"
+
+[makepad]
+entry = "app"
+[makepad.wasm]
+prot = 8016
+
+[plugins]
+http = ".plugins/http"
+empty = ".plugins/empty"
+
+[compilr]
+"#
+        .parse::<DocumentMut>()
+        .unwrap();
+        let warnings = validate(&toml, dir, "makepad")
+            .iter()
+            .map(ToString::to_string)
+            .collect::<Vec<String>>();
+
+        assert_eq!(
+            warnings[..6],
+            [
+                "unknown [compilr], did you mean `compiler`?",
+                "unknown `log_lvl` in [compiler], did you mean `log_level`?",
+                "unknown `exclude` in [compiler], did you mean `excludes`?",
+                "`logo` in [compiler] should be bool, the default value is used",
+                "unknown `after` in [compiler.hooks]",
+                "unknown `prot` in [makepad.wasm], did you mean `port`?",
+            ]
+        );
+        assert!(warnings[6].starts_with("plugin `empty` in [plugins] has no token.toml"));
+        assert_eq!(warnings.len(), 7);
+        assert!(validate(&"[dioxus]\nanything = 1".parse().unwrap(), dir, "dioxus").is_empty());
+        let outputs = "[[compiler.outputs]]\ntarget = \"dioxus\"\npth = \"src_gen_dioxus\"\n\n[dioxus]\n";
+        assert_eq!(
+            validate(&outputs.parse().unwrap(), dir, "makepad")
+                .iter()
+                .map(ToString::to_string)
+                .collect::<Vec<String>>(),
+            ["unknown `pth` in [compiler.outputs], did you mean `path`?"]
+        );
+        assert_eq!(
+            validate(&"[plugins]\nhttp = 1".parse().unwrap(), dir, "makepad"),
+            vec![ConfWarning::Plugin {
+                name: "http".to_string(),
+                reason: "should be the path of the plugin dir".to_string()
+            }]
+        );
+    }
+}
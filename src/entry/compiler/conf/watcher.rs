@@ -0,0 +1,75 @@
+use gen_utils::error::Error;
+use toml_edit::{value, Item, Table};
+
+/// # Watcher Config
+/// how the watcher listens to file changes
+/// ```toml
+/// [compiler.watcher]
+/// # use a polling watcher instead of the native file events, for network file systems or containers
+/// poll = false
+/// # interval of the polling watcher in milliseconds
+/// poll_interval = 1000
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct WatcherConf {
+    pub poll: bool,
+    pub poll_interval: u64,
+}
+
+impl Default for WatcherConf {
+    fn default() -> Self {
+        Self {
+            poll: false,
+            poll_interval: 1000,
+        }
+    }
+}
+
+impl WatcherConf {
+    pub fn is_default(&self) -> bool {
+        *self == Self::default()
+    }
+}
+
+impl TryFrom<&Item> for WatcherConf {
+    type Error = Error;
+
+    fn try_from(value: &Item) -> Result<Self, Self::Error> {
+        let table = value
+            .as_table()
+            .ok_or_else(|| Error::from("[compiler.watcher] must be a table"))?;
+        let default = Self::default();
+
+        let poll = table
+            .get("poll")
+            .and_then(|item| item.as_bool())
+            .unwrap_or(default.poll);
+        let poll_interval = table
+            .get("poll_interval")
+            .and_then(|item| item.as_integer())
+            .map_or_else(
+                || Ok(default.poll_interval),
+                |interval| {
+                    u64::try_from(interval).map_err(|_| {
+                        Error::from(
+                            "[compiler.watcher] poll_interval must be a non-negative integer",
+                        )
+                    })
+                },
+            )?;
+
+        Ok(Self {
+            poll,
+            poll_interval,
+        })
+    }
+}
+
+impl From<&WatcherConf> for Item {
+    fn from(conf: &WatcherConf) -> Self {
+        let mut table = Table::new();
+        table.insert("poll", value(conf.poll));
+        table.insert("poll_interval", value(conf.poll_interval as i64));
+        Item::Table(table)
+    }
+}
@@ -1,35 +1,147 @@
 use std::path::{Path, PathBuf};
 
-use gen_utils::{common::fs, error::Error};
+use gen_utils::error::Error;
+use ignore::{
+    gitignore::{Gitignore, GitignoreBuilder},
+    Match,
+};
 use toml_edit::{Array, Formatted, Value};
 
+use super::service::is_cache_file;
+
 /// # Gen Excludes
 /// These files and directories are excludesd by the compiler(watcher)
 /// Which need to write in `gen_ui.toml` file
+///
+/// Each item is a gitignore-style pattern which is relative to the GenUI project:
+/// - `target`: file or dir named `target` at any level, except the default names (see `ROOT_ONLY`) which are
+///   matched from the project root as before, use `**/target` to match them at any level
+/// - `/src/main.rs`, `views/a.gen`: pattern with `/` is matched from the project root
+/// - `**/*.swp`, `*.bak`, `.#*`: glob patterns
+/// - `!views/keep.gen`: negation, include the file again
 /// ## Example
 /// ```toml
 /// [compiler]
-/// excludes: ["Cargo.toml", "Cargo.lock", "src/main.rs", "target", ".gen_ui_cache"]
+/// excludes: ["Cargo.toml", "Cargo.lock", "src/main.rs", "target", ".gen_ui_cache", "*.swp"]
+/// use_gitignore = true
 /// ```
 /// ## Default Excludes
-/// ["Cargo.toml", "Cargo.lock", "target", ".gen_ui_cache", "gen_ui.toml", ".plugins"]
+/// ["/Cargo.toml", "/Cargo.lock", "/target", "/.gen_ui_cache", "/gen_ui.toml", "/.plugins", "*.swp", "*~", ".#*", "4913"]
 #[derive(Debug, Clone)]
-pub struct Excludes(pub Vec<PathBuf>);
+pub struct Excludes(pub Vec<String>);
+
+/// names which are excluded from the project root only, they are written without `/` in gen_ui.toml
+/// before excludes are gitignore-style
+const ROOT_ONLY: [&str; 6] = [
+    "Cargo.toml",
+    "Cargo.lock",
+    "target",
+    ".gen_ui_cache",
+    "gen_ui.toml",
+    ".plugins",
+];
 
 impl Excludes {
-    pub fn contains<P>(&self, prefix: P, path: P) -> bool
+    /// ## build the matcher of excludes
+    /// - root: the GenUI project path, patterns are relative to it
+    /// - use_gitignore: also respect `.gitignore` in the project and its workspace
+    pub fn matcher<P>(&self, root: P, use_gitignore: bool) -> Result<ExcludeMatcher, Error>
     where
         P: AsRef<Path>,
     {
-        self.0.iter().any(|p| {
-            let p = prefix.as_ref().join(p);
-            // || path.as_ref().starts_with(&p)
-            p == path.as_ref() || path.as_ref().starts_with(&p)
+        let root = root.as_ref();
+        let mut matchers = vec![];
+        // [.gitignore] --------------------------------------------------------------------------------------
+        // workspace first, then project, so that patterns in the project have higher priority
+        if use_gitignore {
+            let dirs = root
+                .parent()
+                .into_iter()
+                .chain(std::iter::once(root))
+                .filter(|dir| dir.join(".gitignore").is_file());
+
+            for dir in dirs {
+                let mut builder = GitignoreBuilder::new(dir);
+                if let Some(e) = builder.add(dir.join(".gitignore")) {
+                    return Err(Error::from(e.to_string()));
+                }
+                matchers.push(build(&builder)?);
+            }
+        }
+        // [excludes] ----------------------------------------------------------------------------------------
+        let mut builder = GitignoreBuilder::new(root);
+        for pattern in self.0.iter() {
+            let pattern = if ROOT_ONLY.contains(&pattern.as_str()) {
+                format!("/{}", pattern)
+            } else {
+                pattern.to_string()
+            };
+            builder.add_line(None, &pattern).map_err(|e| {
+                Error::from(format!("invalid exclude pattern `{}`: {}", pattern, e))
+            })?;
+        }
+        matchers.push(build(&builder)?);
+
+        Ok(ExcludeMatcher {
+            root: root.to_path_buf(),
+            matchers,
         })
     }
 }
 
-impl From<Excludes> for Vec<PathBuf> {
+fn build(builder: &GitignoreBuilder) -> Result<Gitignore, Error> {
+    builder.build().map_err(|e| Error::from(e.to_string()))
+}
+
+/// # Exclude Matcher
+/// matcher built from [Excludes] (and `.gitignore`), shared by the watcher and `compile_all`
+#[derive(Debug, Clone)]
+pub struct ExcludeMatcher {
+    root: PathBuf,
+    /// matchers from low priority to high priority
+    matchers: Vec<Gitignore>,
+}
+
+impl ExcludeMatcher {
+    /// ## check the path is excluded or not
+    /// a path is excluded if itself or any of its parent dirs is matched,
+    /// the last matched pattern decides the result (same as git). cache files are always excluded
+    pub fn is_excluded<P>(&self, path: P) -> bool
+    where
+        P: AsRef<Path>,
+    {
+        self.is_excluded_as(path.as_ref(), path.as_ref().is_dir())
+    }
+    /// ## check the path as a dir or a file
+    /// a removed path is not a dir on the disk any more, dir-only patterns (`scratch/`) match it only if
+    /// the caller knows it was a dir (such as the remove event of the watcher or the cache)
+    pub fn is_excluded_as<P>(&self, path: P, is_dir: bool) -> bool
+    where
+        P: AsRef<Path>,
+    {
+        let path = path.as_ref();
+        // path not in the project, can not be excluded
+        if path == self.root.as_path() || !path.starts_with(self.root.as_path()) {
+            return false;
+        }
+        if is_cache_file(path) {
+            return true;
+        }
+
+        self.matchers
+            .iter()
+            .filter(|matcher| path.starts_with(matcher.path()))
+            .fold(false, |excluded, matcher| {
+                match matcher.matched_path_or_any_parents(path, is_dir) {
+                    Match::None => excluded,
+                    Match::Ignore(_) => true,
+                    Match::Whitelist(_) => false,
+                }
+            })
+    }
+}
+
+impl From<Excludes> for Vec<String> {
     fn from(value: Excludes) -> Self {
         value.0
     }
@@ -37,14 +149,24 @@ impl From<Excludes> for Vec<PathBuf> {
 
 impl Default for Excludes {
     fn default() -> Self {
-        Self(vec![
-            PathBuf::from("Cargo.toml"),
-            PathBuf::from("Cargo.lock"),
-            PathBuf::from("target"),
-            PathBuf::from(".gen_ui_cache"),
-            PathBuf::from("gen_ui.toml"),
-            PathBuf::from(".plugins"),
-        ])
+        Self(
+            [
+                "/Cargo.toml",
+                "/Cargo.lock",
+                "/target",
+                "/.gen_ui_cache",
+                "/gen_ui.toml",
+                "/.plugins",
+                // editor temp files
+                "*.swp",
+                "*~",
+                ".#*",
+                "4913",
+            ]
+            .into_iter()
+            .map(ToString::to_string)
+            .collect(),
+        )
     }
 }
 
@@ -56,10 +178,10 @@ impl TryFrom<&Array> for Excludes {
             .iter()
             .map(|item| {
                 item.as_str()
-                    .map(|s| PathBuf::from(s))
+                    .map(|s| s.to_string())
                     .ok_or_else(|| Error::from("Excludes must be a string"))
             })
-            .collect::<Result<Vec<PathBuf>, Error>>()
+            .collect::<Result<Vec<String>, Error>>()
             .map(|v| Excludes(v))
     }
 }
@@ -69,8 +191,53 @@ impl Into<Value> for &Excludes {
         Value::Array(
             self.0
                 .iter()
-                .map(|p| Value::String(Formatted::new(fs::path_to_str(p))))
+                .map(|p| Value::String(Formatted::new(p.to_string())))
                 .collect(),
         )
     }
 }
+
+#[cfg(test)]
+mod test_excludes {
+    use std::path::PathBuf;
+
+    use super::Excludes;
+
+    #[test]
+    fn patterns() {
+        let root = PathBuf::from("/project/hello");
+        let excludes = Excludes(
+            [
+                "target",
+                "/src/main.rs",
+                "**/*.swp",
+                "scratch_*/",
+                "*.bak",
+                "!keep.bak",
+            ]
+            .into_iter()
+            .map(ToString::to_string)
+            .collect(),
+        );
+        let matcher = excludes.matcher(root.as_path(), false).unwrap();
+
+        assert!(matcher.is_excluded(root.join("target/debug/app")));
+        assert!(matcher.is_excluded(root.join("src/main.rs")));
+        assert!(!matcher.is_excluded(root.join("views/src/main.rs")));
+        assert!(matcher.is_excluded(root.join("views/.home.gen.swp")));
+        assert!(matcher.is_excluded(root.join("scratch_1/notes.gen")));
+        assert!(matcher.is_excluded(root.join("old.bak")));
+        assert!(!matcher.is_excluded(root.join("keep.bak")));
+        assert!(!matcher.is_excluded(root.join("views/home.gen")));
+        assert!(!matcher.is_excluded(PathBuf::from("/other/target")));
+        // `target` written before excludes are gitignore-style is only the dir in the project root
+        assert!(!matcher.is_excluded(root.join("views/target/home.gen")));
+        // removed dir
+        assert!(!matcher.is_excluded_as(root.join("scratch_2"), false));
+        assert!(matcher.is_excluded_as(root.join("scratch_2"), true));
+
+        let matcher = Excludes::default().matcher(root.as_path(), false).unwrap();
+        assert!(matcher.is_excluded(root.join("Cargo.toml")));
+        assert!(!matcher.is_excluded(root.join("views/Cargo.toml")));
+    }
+}
@@ -0,0 +1,241 @@
+use std::{
+    fmt::Display,
+    path::{Path, PathBuf},
+    process::{Command, Stdio},
+};
+
+use gen_utils::{
+    common::{fs, stream_terminal},
+    error::Error,
+};
+use toml_edit::{value, Item, Table};
+
+use crate::log::compiler::CompilerLogger;
+
+/// # Compiler Hooks
+/// shell commands which run around the compilation, which need to write in `gen_ui.toml` file
+///
+/// - before_all: run once before the first compile (in `init`)
+/// - after_all: run after the first compile and after each compile pass of the watcher
+/// - after_file: run after a file is compiled by the watcher
+///
+/// commands run in the GenUI project dir, these placeholders (and env vars) can be used:
+/// - `{project}` (`GENUI_HOOK_PROJECT`): the GenUI project path
+/// - `{compiled}` (`GENUI_HOOK_COMPILED`): the compiled project path
+/// - `{source}` (`GENUI_HOOK_SOURCE`): the source file, only for `after_file`
+/// - `{output}` (`GENUI_HOOK_OUTPUT`): the compiled file, only for `after_file`
+/// ## Example
+/// ```toml
+/// [compiler.hooks]
+/// before_all = "python scripts/codegen.py"
+/// after_all = "cargo fmt --manifest-path {compiled}/Cargo.toml"
+/// after_file = "echo {source} -> {output}"
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct Hooks {
+    pub before_all: Option<String>,
+    pub after_all: Option<String>,
+    pub after_file: Option<String>,
+}
+
+impl Hooks {
+    pub fn is_empty(&self) -> bool {
+        self.before_all.is_none() && self.after_all.is_none() && self.after_file.is_none()
+    }
+    pub fn get(&self, hook: Hook) -> Option<&String> {
+        match hook {
+            Hook::BeforeAll => self.before_all.as_ref(),
+            Hook::AfterAll => self.after_all.as_ref(),
+            Hook::AfterFile => self.after_file.as_ref(),
+        }
+    }
+    /// ## run the hook
+    /// output of the command is streamed through the compiler logger.
+    /// return error if the command can not be started or exits with failure, do nothing if the hook is not set
+    pub fn run(&self, hook: Hook, vars: &HookVars) -> Result<(), Error> {
+        let Some(command) = self.get(hook) else {
+            return Ok(());
+        };
+
+        let command = vars.replace(command);
+        let mut child = shell(command.as_str())
+            .current_dir(vars.project.as_path())
+            .envs(vars.envs())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()
+            .map_err(|e| Error::from(format!("hook {} can not start: {}", hook, e)))?;
+
+        let status = stream_terminal(
+            &mut child,
+            move |line| CompilerLogger::new(&format!("[{}] {}", hook, line)).info(),
+            move |line| CompilerLogger::new(&format!("[{}] {}", hook, line)).warn(),
+        )?;
+
+        if status.success() {
+            Ok(())
+        } else {
+            Err(Error::from(format!(
+                "hook {} `{}` failed: {}",
+                hook, command, status
+            )))
+        }
+    }
+}
+
+/// command which runs in the platform shell, shared by hooks and external underlayers
+#[cfg(target_os = "windows")]
+pub fn shell(command: &str) -> Command {
+    let mut cmd = Command::new("cmd");
+    cmd.args(["/C", command]);
+    cmd
+}
+
+#[cfg(not(target_os = "windows"))]
+pub fn shell(command: &str) -> Command {
+    let mut cmd = Command::new("sh");
+    cmd.args(["-c", command]);
+    cmd
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Hook {
+    BeforeAll,
+    AfterAll,
+    AfterFile,
+}
+
+impl Display for Hook {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            Hook::BeforeAll => "before_all",
+            Hook::AfterAll => "after_all",
+            Hook::AfterFile => "after_file",
+        })
+    }
+}
+
+/// # Hook Variables
+/// values of the placeholders in hook commands
+#[derive(Debug, Clone)]
+pub struct HookVars {
+    pub project: PathBuf,
+    pub compiled: PathBuf,
+    pub source: Option<PathBuf>,
+    pub output: Option<PathBuf>,
+}
+
+impl HookVars {
+    pub fn new<P>(project: P, compiled: P) -> Self
+    where
+        P: AsRef<Path>,
+    {
+        Self {
+            project: project.as_ref().to_path_buf(),
+            compiled: compiled.as_ref().to_path_buf(),
+            source: None,
+            output: None,
+        }
+    }
+    pub fn with_file<P>(mut self, source: P, output: P) -> Self
+    where
+        P: AsRef<Path>,
+    {
+        self.source.replace(source.as_ref().to_path_buf());
+        self.output.replace(output.as_ref().to_path_buf());
+        self
+    }
+    fn vars(&self) -> Vec<(&'static str, &'static str, String)> {
+        let mut vars = vec![
+            (
+                "{project}",
+                "GENUI_HOOK_PROJECT",
+                fs::path_to_str(&self.project),
+            ),
+            (
+                "{compiled}",
+                "GENUI_HOOK_COMPILED",
+                fs::path_to_str(&self.compiled),
+            ),
+        ];
+        if let Some(source) = self.source.as_ref() {
+            vars.push(("{source}", "GENUI_HOOK_SOURCE", fs::path_to_str(source)));
+        }
+        if let Some(output) = self.output.as_ref() {
+            vars.push(("{output}", "GENUI_HOOK_OUTPUT", fs::path_to_str(output)));
+        }
+        vars
+    }
+    /// replace placeholders in the command
+    pub fn replace(&self, command: &str) -> String {
+        self.vars()
+            .into_iter()
+            .fold(command.to_string(), |command, (placeholder, _, value)| {
+                command.replace(placeholder, &value)
+            })
+    }
+    pub fn envs(&self) -> Vec<(&'static str, String)> {
+        self.vars()
+            .into_iter()
+            .map(|(_, key, value)| (key, value))
+            .collect()
+    }
+}
+
+impl TryFrom<&Item> for Hooks {
+    type Error = Error;
+
+    fn try_from(value: &Item) -> Result<Self, Self::Error> {
+        let table = value
+            .as_table()
+            .ok_or_else(|| Error::from("[compiler.hooks] must be a table"))?;
+
+        let get = |key: &str| -> Result<Option<String>, Error> {
+            table.get(key).map_or(Ok(None), |item| {
+                item.as_str().map(|s| Some(s.to_string())).ok_or_else(|| {
+                    Error::from(format!("[compiler.hooks] {} must be a string", key))
+                })
+            })
+        };
+
+        Ok(Self {
+            before_all: get("before_all")?,
+            after_all: get("after_all")?,
+            after_file: get("after_file")?,
+        })
+    }
+}
+
+impl From<&Hooks> for Item {
+    fn from(hooks: &Hooks) -> Self {
+        let mut table = Table::new();
+        for hook in [Hook::BeforeAll, Hook::AfterAll, Hook::AfterFile] {
+            if let Some(command) = hooks.get(hook) {
+                table.insert(&hook.to_string(), value(command));
+            }
+        }
+        Item::Table(table)
+    }
+}
+
+#[cfg(test)]
+mod test_hooks {
+    use super::HookVars;
+
+    #[test]
+    fn replace() {
+        let vars = HookVars::new("/project/hello", "/project/src_gen_0").with_file(
+            "/project/hello/views/home.gen",
+            "/project/src_gen_0/src/views/home.rs",
+        );
+
+        assert_eq!(
+            vars.replace("echo {source} -> {output} in {compiled}"),
+            "echo /project/hello/views/home.gen -> /project/src_gen_0/src/views/home.rs in /project/src_gen_0"
+        );
+        assert_eq!(
+            HookVars::new("/project/hello", "/project/src_gen_0").replace("echo {source}"),
+            "echo {source}"
+        );
+    }
+}
@@ -4,10 +4,28 @@ mod target;
 mod conf;
 /// Exclude files or directories when compiling or watching
 mod excludes;
+/// Commands run around the compilation
+mod hooks;
+/// Registry of the underlayer compilers (builtin, in-process and external)
+mod registry;
+/// Bootstrap the workspace manifest of the GenUI project
+mod workspace;
 mod service;
 mod ract_toml;
 
 pub use target::Underlayer;
-pub use conf::Conf as GenUIConf;
-pub use service::Compiler;
-pub use ract_toml::{RactToml, Member};
\ No newline at end of file
+pub use registry::{
+    register as register_underlayer, unregister as unregister_underlayer, CustomConf,
+    ExternalCompiler, UnderlayerFactory,
+};
+pub use conf::{
+    CompilerConf, Conf as GenUIConf, ConfOverrides, ConfWarning, Layer, Layers, OutputConf,
+    WatcherConf,
+};
+pub use service::{
+    is_cache_file, Cache, Compiler, Daemon, DaemonStatus, Diagnostic, DiagnosticFormat, Diagnostics,
+    Metrics, Phase, Severity, SourceLocation, SourceMap, SOURCE_MAP,
+};
+pub use ract_toml::{RactToml, Member};
+pub use excludes::Excludes;
+pub use workspace::{check as check_workspace, repair as repair_workspace};
\ No newline at end of file
@@ -11,7 +11,10 @@ use gen_utils::{
 };
 use toml_edit::{value, Array, DocumentMut, Formatted, InlineTable, Value};
 
-use crate::entry::{FrameworkType, ProjectInfo, Resource};
+use crate::{
+    common::TomlOrigin,
+    entry::{FrameworkType, ProjectInfo, Resource},
+};
 
 /// # RactToml
 /// each project has a .ract file to point the project kind and help ract to compile the project
@@ -37,6 +40,8 @@ pub struct RactToml {
     /// - if compiles length is more than 1, use multiple threads to compile the projects
     pub compiles: Option<Vec<usize>>,
     pub resources: Vec<Resource>,
+    /// .ract as it is read, `write` only edits the changed keys of it
+    origin: Option<TomlOrigin>,
 }
 
 impl RactToml {
@@ -89,6 +94,7 @@ impl RactToml {
             members,
             compiles,
             resources: target.resources_in_ract(),
+            origin: None,
         }
     }
 }
@@ -105,6 +111,18 @@ impl TryFrom<&PathBuf> for RactToml {
 }
 
 impl ToToml for RactToml {
+    /// ## write .ract
+    /// a RactToml read from the file only writes the keys which are changed since then, see [TomlOrigin]
+    fn write<P>(&self, path: P) -> Result<(), Error>
+    where
+        P: AsRef<Path>,
+    {
+        match self.origin.as_ref() {
+            Some(origin) => fs::write(path, &origin.edit(&self.to_toml()).to_string()),
+            None => fs::write(path, &self.to_string()),
+        }
+    }
+
     fn to_toml(&self) -> toml_edit::DocumentMut {
         let mut doc = DocumentMut::new();
         doc.insert("target", self.target.into());
@@ -217,12 +235,15 @@ impl TryFrom<&DocumentMut> for RactToml {
             },
         )?;
 
-        Ok(Self {
+        let mut ract_toml = Self {
             target,
             members,
             compiles,
             resources,
-        })
+            origin: None,
+        };
+        ract_toml.origin = Some(TomlOrigin::new(value.clone(), ract_toml.to_toml()));
+        Ok(ract_toml)
     }
 }
 
@@ -304,9 +325,13 @@ impl TryFrom<&Value> for Member {
 
 #[cfg(test)]
 mod test_ract {
+    use gen_utils::common::ToToml;
     use toml_edit::DocumentMut;
 
-    use crate::entry::RactToml;
+    use crate::{
+        common::TempDir,
+        entry::{Member, RactToml},
+    };
 
     #[test]
     fn makepad() {
@@ -334,4 +359,38 @@ mod test_ract {
         let ract = RactToml::try_from(&toml).unwrap();
         println!("{}", ract);
     }
+
+    #[test]
+    fn write_minimal() {
+        let input = r#"# my app
+target = "gen_ui"
+members = [
+    { src = "hello", target = "src_gen_0" }, # main app
+]
+resources = ["assets"]
+owner = "me"
+"#;
+        let tmp = TempDir::new("ract_write").unwrap();
+        let path = tmp.path().join(".ract");
+        let mut ract = RactToml::try_from(&input.parse::<DocumentMut>().unwrap()).unwrap();
+        ract.members.as_mut().unwrap().push(Member {
+            source: "world".into(),
+            target: "src_gen_1".into(),
+        });
+        ract.write(path.as_path()).unwrap();
+        let output = std::fs::read_to_string(path.as_path()).unwrap();
+
+        assert_eq!(
+            output,
+            r#"# my app
+target = "gen_ui"
+members = [
+    { src = "hello", target = "src_gen_0" }, # main app
+    { src = "world", target = "src_gen_1" },
+]
+resources = ["assets"]
+owner = "me"
+"#
+        );
+    }
 }
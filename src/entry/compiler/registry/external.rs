@@ -0,0 +1,357 @@
+use std::{
+    collections::HashMap,
+    io::{BufRead, BufReader, Write},
+    path::{Path, PathBuf},
+    process::{Child, ChildStdin, Stdio},
+    sync::mpsc::{channel, Receiver, RecvTimeoutError},
+    thread,
+    time::{Duration, Instant},
+};
+
+use gen_utils::{
+    common::{fs, Source},
+    compiler::{CompilerImpl, Executor},
+    error::Error,
+};
+use serde_json::{json, Value};
+use toml_edit::DocumentMut;
+
+use crate::{entry::compiler::hooks::shell, log::compiler::CompilerLogger};
+
+use super::CustomConf;
+
+/// time to wait for the response of a request
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(60);
+
+/// # External Compiler
+/// an underlayer compiler which runs in another process and speaks JSON-RPC 2.0 by stdin/stdout
+///
+/// - the command is `[<name>] command` in gen_ui.toml (default `genui-<name>`), it runs in the workspace dir
+/// - each request and response is a json object in one line, requests are sent one by one
+/// - stdout is only for responses (other lines are logged), use stderr to print logs
+/// - the process should exit when stdin is closed
+/// - a process which does not respond in 60s is killed and started again (then `init` is sent again)
+///
+/// ## Methods
+/// - `init`: `{"project": "/ws", "source": "/ws/hello", "compiled": "/ws/src_gen_0", "conf": "<toml>", "plugins": {}}`,
+///   `conf` is the `[<name>]` table without `command`, `compiled` is a cargo project created by ract
+/// - `compile`: `{"path": "/ws/hello/views/home.gen"}`, compile the file (it is called for `.gen` files and the entry).
+///   on startup, an unchanged `.gen` file whose output (`/ws/src_gen_0/src/views/home.rs`) exists is not sent again
+/// - `remove`: `{"path": "/ws/hello/views/home.gen"}`, result is `null` or the removed compiled files
+/// - `update`: `{}`, called after each compile pass, update the compiled project (such as `main.rs`)
+///
+/// ## Example
+/// ```text
+/// -> {"jsonrpc":"2.0","id":2,"method":"compile","params":{"path":"/ws/hello/views/home.gen"}}
+/// <- {"jsonrpc":"2.0","id":2,"result":null}
+/// -> {"jsonrpc":"2.0","id":3,"method":"remove","params":{"path":"/ws/hello/views/old.gen"}}
+/// <- {"jsonrpc":"2.0","id":3,"error":{"code":-32000,"message":"old.gen is not compiled"}}
+/// ```
+pub struct ExternalCompiler {
+    name: String,
+    source: Source,
+    conf: CustomConf,
+    plugins: HashMap<String, PathBuf>,
+    child: Child,
+    stdin: Option<ChildStdin>,
+    /// lines of stdout, read in a thread so that a request can time out
+    stdout: Receiver<String>,
+    id: u64,
+    timeout: Duration,
+}
+
+impl ExternalCompiler {
+    /// ## start the external compiler process
+    pub fn spawn(source: &Source, conf: &CustomConf) -> Result<Self, Error> {
+        let command = conf.command();
+        let mut child = shell(command.as_str())
+            .current_dir(source.path.as_path())
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()
+            .map_err(|e| {
+                Error::from(format!(
+                    "underlayer `{}` can not start `{}`: {}",
+                    conf.name, command, e
+                ))
+            })?;
+
+        let (stdin, stdout, stderr) =
+            (child.stdin.take(), child.stdout.take(), child.stderr.take());
+        let (Some(stdin), Some(stdout), Some(stderr)) = (stdin, stdout, stderr) else {
+            let _ = child.kill();
+            return Err(Error::from(format!(
+                "underlayer `{}` can not capture stdio",
+                conf.name
+            )));
+        };
+        // [stderr as logs] --------------------------------------------------------------------------------------
+        let name = conf.name.to_string();
+        thread::spawn(move || {
+            for line in BufReader::new(stderr).lines().map_while(Result::ok) {
+                CompilerLogger::new(&format!("[{}] {}", name, line)).info();
+            }
+        });
+        // [stdout] ----------------------------------------------------------------------------------------------
+        let (tx, rx) = channel();
+        thread::spawn(move || {
+            for line in BufReader::new(stdout).lines().map_while(Result::ok) {
+                if tx.send(line).is_err() {
+                    break;
+                }
+            }
+        });
+
+        Ok(Self {
+            name: conf.name.to_string(),
+            source: source.clone(),
+            conf: conf.clone(),
+            plugins: HashMap::new(),
+            child,
+            stdin: Some(stdin),
+            stdout: rx,
+            id: 0,
+            timeout: REQUEST_TIMEOUT,
+        })
+    }
+    /// ## send the request and wait for the response
+    /// the process is restarted if it does not respond in time, see `restart`
+    fn request(&mut self, method: &str, params: Value) -> Result<Value, Error> {
+        self.id += 1;
+        let id = self.id;
+        let name = self.name.to_string();
+        let exited = |e: String| Error::from(format!("underlayer `{}` exited: {}", name, e));
+
+        let stdin = self
+            .stdin
+            .as_mut()
+            .ok_or_else(|| exited("stdin is closed".to_string()))?;
+        let line = json!({"jsonrpc": "2.0", "id": id, "method": method, "params": params});
+        writeln!(stdin, "{}", line)
+            .and_then(|_| stdin.flush())
+            .map_err(|e| exited(e.to_string()))?;
+
+        let deadline = Instant::now() + self.timeout;
+        loop {
+            let line = match self
+                .stdout
+                .recv_timeout(deadline.saturating_duration_since(Instant::now()))
+            {
+                Ok(line) => line,
+                Err(RecvTimeoutError::Disconnected) => {
+                    return Err(exited(format!("no response of `{}`", method)));
+                }
+                Err(RecvTimeoutError::Timeout) => {
+                    let err = Error::from(format!(
+                        "underlayer `{}` did not respond to `{}` in {}ms",
+                        name,
+                        method,
+                        self.timeout.as_millis()
+                    ));
+                    self.restart(method);
+                    return Err(err);
+                }
+            };
+            let response = match serde_json::from_str::<Value>(line.trim()) {
+                Ok(response) if response.get("id").and_then(Value::as_u64) == Some(id) => response,
+                // not a response of the request, such as a `println!` of the compiler
+                _ => {
+                    if !line.trim().is_empty() {
+                        CompilerLogger::new(&format!("[{}] {}", name, line.trim_end())).info();
+                    }
+                    continue;
+                }
+            };
+
+            return match response.get("error") {
+                Some(error) => Err(Error::from(format!(
+                    "underlayer `{}` failed to {}: {}",
+                    name,
+                    method,
+                    error
+                        .get("message")
+                        .and_then(Value::as_str)
+                        .map_or_else(|| error.to_string(), ToString::to_string)
+                ))),
+                None => Ok(response.get("result").cloned().unwrap_or(Value::Null)),
+            };
+        }
+    }
+    /// ## kill the process which does not respond and start a new one
+    /// the new process is initialized again, a process which does not respond to `init` is not restarted
+    fn restart(&mut self, method: &str) {
+        self.kill();
+        if method == "init" {
+            return;
+        }
+        let res = Self::spawn(&self.source, &self.conf).and_then(|mut compiler| {
+            compiler.plugins = self.plugins.clone();
+            compiler.timeout = self.timeout;
+            compiler.init()?;
+            Ok(compiler)
+        });
+        match res {
+            Ok(compiler) => *self = compiler,
+            Err(e) => {
+                CompilerLogger::new(&format!("[{}] can not restart: {}", self.name, e)).error()
+            }
+        }
+    }
+    fn kill(&mut self) {
+        self.stdin.take();
+        let _ = self.child.kill();
+        let _ = self.child.wait();
+    }
+    fn path_params(path: &Path) -> Value {
+        json!({"path": fs::path_to_str(path)})
+    }
+}
+
+impl CompilerImpl for ExternalCompiler {
+    fn execute_auxiliaries(&mut self, executor: Executor) {
+        executor.ignore_fn()
+    }
+
+    fn init(&mut self) -> Result<(), Error> {
+        let mut conf = self.conf.table.clone();
+        conf.remove("command");
+        let plugins = self
+            .plugins
+            .iter()
+            .map(|(name, path)| (name.to_string(), Value::from(fs::path_to_str(path))))
+            .collect::<serde_json::Map<String, Value>>();
+
+        let params = json!({
+            "project": fs::path_to_str(self.source.path.as_path()),
+            "source": fs::path_to_str(self.source.from_path().as_path()),
+            "compiled": fs::path_to_str(self.source.to_path().as_path()),
+            "conf": DocumentMut::from(conf).to_string(),
+            "plugins": plugins,
+        });
+        self.request("init", params).map(|_| ())
+    }
+
+    fn send_plugins(&mut self) -> Result<(), Error> {
+        Ok(())
+    }
+
+    /// plugins are sent to the process in `init`
+    fn recv_plugins(&mut self, plugins: Option<&HashMap<String, PathBuf>>) -> Result<(), Error> {
+        self.plugins = plugins.cloned().unwrap_or_default();
+        Ok(())
+    }
+
+    /// the compiled project is updated after the first compile pass
+    fn before_compile(&mut self) -> Result<(), Error> {
+        self.update()
+    }
+
+    fn after_compile(&mut self) -> Result<(), Error> {
+        Ok(())
+    }
+
+    fn compile(&mut self, path: PathBuf) -> Result<(), Error> {
+        self.request("compile", Self::path_params(path.as_path()))
+            .map(|_| ())
+    }
+
+    fn remove(&mut self, path: PathBuf) -> Result<Option<Vec<PathBuf>>, Error> {
+        let result = self.request("remove", Self::path_params(path.as_path()))?;
+        match result {
+            Value::Null => Ok(None),
+            Value::Array(paths) => paths
+                .iter()
+                .map(|path| {
+                    path.as_str().map(PathBuf::from).ok_or_else(|| {
+                        Error::from(format!(
+                            "underlayer `{}` returned a removed path which is not a string",
+                            self.name
+                        ))
+                    })
+                })
+                .collect::<Result<Vec<PathBuf>, Error>>()
+                .map(Some),
+            _ => Err(Error::from(format!(
+                "underlayer `{}` should return null or an array of paths for remove",
+                self.name
+            ))),
+        }
+    }
+
+    fn update(&mut self) -> Result<(), Error> {
+        self.request("update", json!({})).map(|_| ())
+    }
+}
+
+impl Drop for ExternalCompiler {
+    /// close stdin and wait a moment for the process to exit, kill it if it is still running
+    fn drop(&mut self) {
+        self.stdin.take();
+        let start = Instant::now();
+        while start.elapsed() < Duration::from_secs(2) {
+            match self.child.try_wait() {
+                Ok(None) => thread::sleep(Duration::from_millis(20)),
+                _ => return,
+            }
+        }
+        self.kill();
+    }
+}
+
+#[cfg(all(test, not(target_os = "windows")))]
+mod test_external {
+    use std::{path::PathBuf, time::Duration};
+
+    use gen_utils::{common::Source, compiler::CompilerImpl};
+    use toml_edit::DocumentMut;
+
+    use super::{super::CustomConf, ExternalCompiler};
+
+    #[test]
+    fn protocol() {
+        // answer each request with its id, `remove` fails
+        let doc = r#"[echo]
+command = "while read -r line; do id=$(echo \"$line\" | sed 's/.*\"id\":\\([0-9]*\\).*/\\1/'); case \"$line\" in *remove*) echo '{\"jsonrpc\":\"2.0\",\"id\":'$id',\"error\":{\"code\":-32000,\"message\":\"nope\"}}';; *) echo 'log line'; echo '{\"jsonrpc\":\"2.0\",\"id\":'$id',\"result\":null}';; esac; done"
+"#
+        .parse::<DocumentMut>()
+        .unwrap();
+        let conf = CustomConf::new("echo", doc.get("echo")).unwrap();
+        let dir = std::env::temp_dir();
+        let source = Source::new(dir.clone(), dir.join("hello"), dir.join("src_gen_0"));
+
+        let mut compiler = ExternalCompiler::spawn(&source, &conf).unwrap();
+        compiler.init().unwrap();
+        compiler.compile(PathBuf::from("views/home.gen")).unwrap();
+        compiler.update().unwrap();
+        assert_eq!(
+            compiler
+                .remove(PathBuf::from("views/home.gen"))
+                .err()
+                .unwrap()
+                .to_string(),
+            "underlayer `echo` failed to remove: nope"
+        );
+        assert_eq!(compiler.id, 4);
+    }
+
+    #[test]
+    fn timeout() {
+        // never answer
+        let doc = "[mute]\ncommand = \"while read -r line; do sleep 2; done\"\n"
+            .parse::<DocumentMut>()
+            .unwrap();
+        let conf = CustomConf::new("mute", doc.get("mute")).unwrap();
+        let dir = std::env::temp_dir();
+        let source = Source::new(dir.clone(), dir.join("hello"), dir.join("src_gen_0"));
+
+        let mut compiler = ExternalCompiler::spawn(&source, &conf).unwrap();
+        compiler.timeout = Duration::from_millis(200);
+        assert_eq!(
+            compiler.init().err().unwrap().to_string(),
+            "underlayer `mute` did not respond to `init` in 200ms"
+        );
+        // the process which does not respond is killed
+        assert!(compiler.child.try_wait().unwrap().is_some());
+    }
+}
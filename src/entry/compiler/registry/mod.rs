@@ -0,0 +1,213 @@
+mod external;
+
+use std::{
+    any::Any,
+    fmt::Display,
+    sync::{Arc, RwLock},
+};
+
+use gen_utils::{
+    common::Source,
+    compiler::{CompilerImpl, UnderlayerConfImpl},
+    error::Error,
+};
+use makepad_gen_plugin::compiler::{Compiler as MakepadCompiler, Config as MakepadConfig};
+use toml_edit::{Item, Table};
+
+pub use external::ExternalCompiler;
+
+use super::Underlayer;
+
+/// factory of an in-process underlayer compiler, see [register]
+pub type UnderlayerFactory =
+    dyn Fn(&Source, &dyn UnderlayerConfImpl) -> Result<Box<dyn CompilerImpl>, Error> + Send + Sync;
+
+static UNDERLAYERS: RwLock<Vec<(String, Arc<UnderlayerFactory>)>> = RwLock::new(Vec::new());
+
+/// ## register an in-process underlayer compiler
+/// the compiler is used when `[compiler] target = "<name>"` in gen_ui.toml, the factory receives the source
+/// of the project and a [CustomConf] which holds the `[<name>]` table.
+///
+/// builtin underlayers (see [Underlayer]) can not be replaced, register the same name again replaces the factory
+pub fn register<F>(name: &str, factory: F) -> Result<(), Error>
+where
+    F: Fn(&Source, &dyn UnderlayerConfImpl) -> Result<Box<dyn CompilerImpl>, Error>
+        + Send
+        + Sync
+        + 'static,
+{
+    if name.parse::<Underlayer>().is_ok() {
+        return Err(Error::from(format!(
+            "underlayer `{}` is builtin, it can not be registered",
+            name
+        )));
+    }
+    let mut underlayers = UNDERLAYERS.write().map_err(|e| e.to_string())?;
+    underlayers.retain(|(registered, _)| registered != name);
+    underlayers.push((name.to_string(), Arc::new(factory)));
+    Ok(())
+}
+
+/// ## remove a registered underlayer compiler, return false if it is not registered
+pub fn unregister(name: &str) -> bool {
+    UNDERLAYERS.write().is_ok_and(|mut underlayers| {
+        let len = underlayers.len();
+        underlayers.retain(|(registered, _)| registered != name);
+        underlayers.len() != len
+    })
+}
+
+fn registered(name: &str) -> Option<Arc<UnderlayerFactory>> {
+    UNDERLAYERS.read().ok().and_then(|underlayers| {
+        underlayers
+            .iter()
+            .find(|(registered, _)| registered == name)
+            .map(|(_, factory)| Arc::clone(factory))
+    })
+}
+
+/// ## create the compiler of the target (`[compiler] target` in gen_ui.toml)
+/// targets are resolved in order:
+/// 1. builtin underlayers, see [Underlayer]
+/// 2. in-process compilers added by [register]
+/// 3. external compilers, see [ExternalCompiler]
+pub fn compiler(
+    target: &str,
+    source: &Source,
+    conf: &dyn UnderlayerConfImpl,
+) -> Result<Box<dyn CompilerImpl>, Error> {
+    if let Ok(underlayer) = target.parse::<Underlayer>() {
+        return match underlayer {
+            Underlayer::Makepad => {
+                // the makepad compiler takes a boxed config
+                let conf: Box<dyn UnderlayerConfImpl> = Box::new(
+                    conf.as_any()
+                        .downcast_ref::<MakepadConfig>()
+                        .cloned()
+                        .ok_or_else(|| Error::from("underlayer `makepad` has no makepad config"))?,
+                );
+                Ok(Box::new(MakepadCompiler::new(source.clone(), &conf)?))
+            }
+        };
+    }
+    if let Some(factory) = registered(target) {
+        return factory(source, conf);
+    }
+    let conf = conf
+        .as_any()
+        .downcast_ref::<CustomConf>()
+        .ok_or_else(|| Error::from(format!("underlayer `{}` has no config", target)))?;
+    Ok(Box::new(ExternalCompiler::spawn(source, conf)?))
+}
+
+/// ## the target compiler needs every `.gen` file in each session
+/// builtin underlayers build the widget tree and the app context in memory while compiling, `lib.rs` and the
+/// app main are generated from them, so unchanged files are compiled again on startup. registered and external
+/// compilers only get the changed files and the files whose outputs are missing
+pub fn needs_full_tree(target: &str) -> bool {
+    target.parse::<Underlayer>().is_ok()
+}
+
+/// # Custom Underlayer Config
+/// config of a registered or external underlayer, which is the `[<name>]` table in gen_ui.toml
+/// ```toml
+/// [compiler]
+/// target = "dioxus"
+///
+/// [dioxus]
+/// # command of the external compiler, default is `genui-<name>` (in PATH)
+/// command = "genui-dioxus --release"
+/// # other keys are passed to the compiler as they are
+/// router = true
+/// ```
+#[derive(Debug, Clone)]
+pub struct CustomConf {
+    pub name: String,
+    pub table: Table,
+}
+
+impl CustomConf {
+    pub fn new(name: &str, table: Option<&Item>) -> Result<Self, Error> {
+        let table = match table {
+            Some(item) => item
+                .as_table()
+                .cloned()
+                .ok_or_else(|| Error::from(format!("[{}] in gen_ui.toml must be a table", name)))?,
+            None => Table::new(),
+        };
+        Ok(Self {
+            name: name.to_string(),
+            table,
+        })
+    }
+    /// ## command of the external compiler
+    pub fn command(&self) -> String {
+        self.table
+            .get("command")
+            .and_then(Item::as_str)
+            .map_or_else(|| format!("genui-{}", self.name), ToString::to_string)
+    }
+}
+
+impl Display for CustomConf {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.to_item().to_string())
+    }
+}
+
+impl UnderlayerConfImpl for CustomConf {
+    fn to_item(&self) -> Item {
+        let mut table = Table::new();
+        table.insert(&self.name, Item::Table(self.table.clone()));
+        Item::Table(table)
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+}
+
+#[cfg(test)]
+mod test_registry {
+    use std::path::PathBuf;
+
+    use gen_utils::{common::Source, compiler::UnderlayerConfImpl, error::Error};
+    use toml_edit::DocumentMut;
+
+    use super::{compiler, needs_full_tree, register, unregister, CustomConf};
+
+    #[test]
+    fn resolve() {
+        let doc = "[compiler]\ntarget = \"dummy\"\n\n[dummy]\nlevel = 2\n"
+            .parse::<DocumentMut>()
+            .unwrap();
+        let conf: Box<dyn UnderlayerConfImpl> =
+            Box::new(CustomConf::new("dummy", doc.get("dummy")).unwrap());
+        let source = Source::new(
+            PathBuf::from("/tmp/ws"),
+            PathBuf::from("/tmp/ws/hello"),
+            PathBuf::from("/tmp/ws/src_gen_0"),
+        );
+
+        assert!(register("makepad", |_, _| Err(Error::from("builtin"))).is_err());
+        assert!(needs_full_tree("makepad"));
+        assert!(!needs_full_tree("dummy"));
+        register("dummy", |_, conf| {
+            let conf = conf.as_any().downcast_ref::<CustomConf>().unwrap();
+            Err(Error::from(
+                conf.table["level"].to_string().trim().to_string(),
+            ))
+        })
+        .unwrap();
+        assert_eq!(
+            compiler("dummy", &source, conf.as_ref()).err().unwrap().to_string(),
+            "2"
+        );
+        assert!(unregister("dummy"));
+        assert!(!unregister("dummy"));
+        assert_eq!(
+            CustomConf::new("dummy", None).unwrap().command(),
+            "genui-dummy"
+        );
+    }
+}
@@ -20,14 +20,38 @@ use toml_edit::{value, DocumentMut, Item, Table};
 
 use crate::log::compiler::CompilerLogs;
 
+use super::DepGraph;
+
+/// cache file in the GenUI project, caches of `[[compiler.outputs]]` are `.gen_ui_cache.<output>`
+pub const CACHE_FILE: &str = ".gen_ui_cache";
+
+/// ## is the path a cache file of the compiler
+/// cache files are never compiled, no matter what `[compiler] excludes` is
+pub fn is_cache_file<P>(path: P) -> bool
+where
+    P: AsRef<Path>,
+{
+    path.as_ref().file_name().is_some_and(|name| {
+        let name = name.to_string_lossy();
+        name == CACHE_FILE || name.starts_with(&format!("{}.", CACHE_FILE))
+    })
+}
+
 /// ## Gen compile cache
 /// use msgpack to serialize and deserialize
 #[derive(Clone, Debug)]
 pub struct Cache {
+    /// name of the cache file, see [CACHE_FILE]
+    file: String,
     /// version for the cache
     version: String,
     /// cache values, key is file path, value is file hash value
     values: HashMap<PathBuf, String>,
+    /// imports between `.gen` files
+    graph: DepGraph,
+    /// whether the cache is loaded from `.gen_ui_cache` and matches the current cache version
+    /// (only a trusted cache can be used to do incremental compile)
+    trusted: bool,
 }
 
 impl ToToml for Cache {
@@ -40,24 +64,23 @@ impl ToToml for Cache {
             values_table.insert(&fs::path_to_str(k), value(v));
         }
         toml.insert("values", Item::Table(values_table));
+        if !self.graph.is_empty() {
+            toml.insert("deps", (&self.graph).into());
+        }
         DocumentMut::from(toml)
     }
     fn write<P>(&self, path: P) -> Result<(), Error>
     where
         P: AsRef<Path>,
     {
-        let path = path.as_ref().join(".gen_ui_cache");
+        let path = path.as_ref().join(&self.file);
         fs::write(path, &self.to_string())
     }
     fn read<P>(path: P) -> Result<DocumentMut, Error>
     where
         P: AsRef<Path>,
     {
-        fs::read(path.as_ref().join(".gen_ui_cache")).and_then(|content| {
-            content
-                .parse::<DocumentMut>()
-                .map_err(|e| Error::Parse(ParseError::new(e.to_string().as_str(), ParseType::Toml)))
-        })
+        Cache::load(path.as_ref().join(CACHE_FILE))
     }
 }
 
@@ -128,7 +151,18 @@ impl TryFrom<&DocumentMut> for Cache {
             },
         )?;
 
-        Ok(Self { version, values })
+        // deps is optional, old cache file does not have it
+        let graph = value
+            .get("deps")
+            .map_or_else(|| Ok(DepGraph::default()), DepGraph::try_from)?;
+
+        Ok(Self {
+            file: CACHE_FILE.to_string(),
+            version,
+            values,
+            graph,
+            trusted: false,
+        })
     }
 }
 
@@ -137,14 +171,22 @@ impl Cache {
     /// if the cache file is not exists, create a new empty cache file
     /// if exists, load the cache file
     pub fn new<P>(path: P) -> Result<Cache, Error>
+    where
+        P: AsRef<Path>,
+    {
+        Cache::named(path, CACHE_FILE)
+    }
+    /// load the cache from the file in the path, such as `.gen_ui_cache.src_gen_dioxus`
+    pub fn named<P>(path: P, file: &str) -> Result<Cache, Error>
     where
         P: AsRef<Path>,
     {
         let mut is_load = false;
-        let cache = match Cache::read(path.as_ref()) {
+        let mut cache = match Cache::load(path.as_ref().join(file)) {
             Ok(cache) => {
-                if let Ok(cache) = (&cache).try_into() {
+                if let Ok(mut cache) = Cache::try_from(&cache) {
                     is_load = true;
+                    cache.trusted = cache.version == Cache::default().version;
                     cache
                 } else {
                     Cache::default()
@@ -152,6 +194,7 @@ impl Cache {
             }
             Err(_) => Cache::default(),
         };
+        cache.file = file.to_string();
 
         if !is_load {
             // write the cache file
@@ -161,6 +204,17 @@ impl Cache {
         Ok(cache)
     }
 
+    fn load<P>(path: P) -> Result<DocumentMut, Error>
+    where
+        P: AsRef<Path>,
+    {
+        fs::read(path).and_then(|content| {
+            content
+                .parse::<DocumentMut>()
+                .map_err(|e| Error::Parse(ParseError::new(e.to_string().as_str(), ParseType::Toml)))
+        })
+    }
+
     /// if exists, then calc hash with origin, if hash equal, don't insert and return FileState::Unchanged
     ///
     /// if not exists, insert and return FileState::Created
@@ -171,17 +225,24 @@ impl Cache {
         P: AsRef<Path>,
     {
         let hash = calc_hash(key.as_ref()).map_err(|e| Error::from(e.to_string()))?;
-        if let Some(value) = self.values.get(key.as_ref()) {
-            // exist cache
-            if value.eq(&hash) {
-                return Ok(FileState::Unchanged);
-            } else {
-                self.insert(key, hash);
-                return Ok(FileState::Modified);
-            }
-        } else {
+        let state = self.state(key.as_ref(), &hash);
+        if state.is_modify() {
             self.insert(key, hash);
-            return Ok(FileState::Created);
+        }
+        Ok(state)
+    }
+    /// compare the hash with the cache without insert
+    /// - not exists: FileState::Created
+    /// - exists but hash not equal: FileState::Modified
+    /// - hash equal: FileState::Unchanged
+    pub fn state<P>(&self, key: P, hash: &str) -> FileState
+    where
+        P: AsRef<Path>,
+    {
+        match self.values.get(key.as_ref()) {
+            Some(value) if value.eq(hash) => FileState::Unchanged,
+            Some(_) => FileState::Modified,
+            None => FileState::Created,
         }
     }
     pub fn insert<P>(&mut self, key: P, value: String) -> ()
@@ -193,22 +254,54 @@ impl Cache {
     /// clear the cache `[value]` section and write back to the file
     pub fn clear<P>(&mut self, path: P) -> Result<(), Error> where P: AsRef<Path> {
         self.values.clear();
+        self.graph.clear();
         self.write(path)
     }
+    /// ## is the cache trusted
+    /// a trusted cache is loaded from `.gen_ui_cache` and has the same version as current cache
+    pub fn is_trusted(&self) -> bool {
+        self.trusted
+    }
+    pub fn version(&self) -> &str {
+        &self.version
+    }
+    pub fn graph(&self) -> &DepGraph {
+        &self.graph
+    }
+    pub fn graph_mut(&mut self) -> &mut DepGraph {
+        &mut self.graph
+    }
+    /// get all source paths which are recorded in the cache
+    pub fn sources(&self) -> Vec<PathBuf> {
+        self.values.keys().cloned().collect()
+    }
+    /// the path is a dir which has sources recorded in the cache (it may be removed already)
+    pub fn has_dir<P>(&self, path: P) -> bool
+    where
+        P: AsRef<Path>,
+    {
+        self.values
+            .keys()
+            .any(|source| source != path.as_ref() && source.starts_with(path.as_ref()))
+    }
 
     pub fn remove<P>(&mut self, key: P) -> ()
     where
         P: AsRef<Path>,
     {
         self.values.remove(key.as_ref());
+        self.graph.remove(key.as_ref());
     }
 }
 
 impl Default for Cache {
     fn default() -> Self {
         Self {
+            file: CACHE_FILE.to_string(),
             version: Version::new(0, 0, 2).to_string(),
             values: Default::default(),
+            graph: Default::default(),
+            trusted: false,
         }
     }
 }
@@ -1,6 +1,9 @@
 use std::{
+    io::IsTerminal,
     path::{Path, PathBuf},
     process::Command,
+    sync::{mpsc::channel, Arc},
+    time::Instant,
 };
 
 use gen_utils::{
@@ -8,18 +11,29 @@ use gen_utils::{
         fs::{self, copy_file, GenUIFs},
         read_to_doc, Source, ToToml,
     },
-    compiler::CompilerImpl,
+    compiler::{CompilerImpl, UnderlayerConfImpl},
     error::Error,
 };
-use toml_edit::value;
+use inquire::Confirm;
 use walkdir::WalkDir;
 
 use crate::{
-    entry::{GenUIConf, Member},
+    entry::{
+        compiler::{
+            excludes::ExcludeMatcher,
+            hooks::{Hook, HookVars},
+            registry,
+            workspace::{self, Repair},
+        },
+        ConfOverrides, GenUIConf, Member, OutputConf, RactToml,
+    },
     log::compiler::{CompilerLogger, CompilerLogs},
 };
 
-use super::{init_watcher, Cache};
+use super::{
+    calc_hash, init_watcher, metrics::ms, Cache, Daemon, DepGraph, Diagnostic, Diagnostics,
+    Metrics, OutputStamps, Phase, Severity, SourceMap, WorkerPool, CACHE_FILE,
+};
 
 /// # GenUI Compiler
 /// compiler will compile the file when the file is created or modified
@@ -30,12 +44,43 @@ use super::{init_watcher, Cache};
 pub struct Compiler {
     pub source: Source,
     /// compiler target, default is makepad
-    /// which depends on `gen_ui.toml` file
+    /// which depends on `[compiler] target` in `gen_ui.toml` file, see `registry::compiler`
     pub target: Box<dyn CompilerImpl>,
     /// gen_ui.toml file conf
     pub conf: GenUIConf,
     /// cache of the compiled project
     pub cache: Cache,
+    /// matcher of `[compiler] excludes`, shared by the watcher and `compile_all`
+    pub excludes: Arc<ExcludeMatcher>,
+    /// compile incrementally or not, decided in `init` (see `[compiler] incremental` in gen_ui.toml)
+    pub incremental: bool,
+    /// stamps of the compiled project before incremental compile
+    stamps: Option<OutputStamps>,
+    /// diagnostics of current compile pass
+    pub diagnostics: Diagnostics,
+    /// called after each compile pass without errors, see `on_updated`
+    updated: Option<Box<dyn FnMut()>>,
+    /// timings of the compile phases in the session
+    pub metrics: Metrics,
+    /// JSON-RPC daemon which serves the state of the compiler, see `serve`
+    daemon: Option<Daemon>,
+    /// remove outputs of the sources which are deleted while the compiler is not running (`--no-prune` to turn off)
+    pub prune: bool,
+    /// fail in `init` if gen_ui.toml has warnings (`--strict`), see `ConfWarning`
+    pub strict: bool,
+    /// extra outputs in `[[compiler.outputs]]` and their underlayer configs (moved out of `conf.underlayer.others`),
+    /// the compilers of them are created in `init`
+    pending: Vec<(OutputConf, Box<dyn UnderlayerConfImpl>)>,
+    /// compilers of the extra outputs, each one has its own target, cache and diagnostics.
+    /// they receive the same file changes as this compiler, see `on_change`
+    outputs: Vec<Compiler>,
+    /// outputs (`[compiler] target` or extra ones) which failed to init or compile in the first compile
+    failed: Vec<String>,
+    /// `[compiler] target` is prepared in `init` or not, a failed one is recorded in `failed` like the
+    /// extra outputs and skipped, the extra outputs keep running
+    prepared: bool,
+    /// name (path) of the extra output, `None` for the compiler of `[compiler] target`
+    output: Option<String>,
     // /// context of the compiler
     // pub context: Context,
 }
@@ -49,22 +94,141 @@ impl Compiler {
         // [source] --------------------------------------------------------------------------------------
         let source = member.to_source(path.as_ref());
         // [conf] ----------------------------------------------------------------------------------------
-        let conf: GenUIConf = GenUIConf::new(source_path.as_path())?;
-        // [target] --------------------------------------------------------------------------------------
-        let target = conf
+        let mut conf: GenUIConf = GenUIConf::new(source_path.as_path())?;
+        conf.compiler.apply_env(|key| std::env::var(key).ok())?;
+        // [outputs] -------------------------------------------------------------------------------------
+        // an output can not overwrite the compiled project of any member in `.ract`
+        let mut targets = vec![member.target.clone()];
+        let ract_path = path.as_ref().join(".ract");
+        if ract_path.exists() {
+            let ract_toml = RactToml::try_from(&ract_path)?;
+            targets.extend(ract_toml.members.into_iter().flatten().map(|m| m.target));
+        }
+        if let Some(output) = conf
+            .compiler
+            .outputs
+            .iter()
+            .find(|output| targets.contains(&output.path))
+        {
+            return Err(Error::from(format!(
+                "[[compiler.outputs]] path `{}` is the compiled project of a member in .ract",
+                fs::path_to_str(&output.path)
+            )));
+        }
+        let pending = conf
             .compiler
-            .target
-            .compiler(&source, &conf.underlayer.target)?;
+            .outputs
+            .iter()
+            .cloned()
+            .zip(conf.underlayer.others.take().unwrap_or_default())
+            .collect();
+        // [excludes] ------------------------------------------------------------------------------------
+        let excludes = Arc::new(
+            conf.compiler
+                .excludes
+                .matcher(source_path.as_path(), conf.compiler.use_gitignore)?,
+        );
+
+        let mut compiler = Self::with_conf(source, conf, CACHE_FILE, excludes)?;
+        compiler.pending = pending;
+        Ok(compiler)
+    }
+    /// ## compiler of an extra output which compiles the same source into `output.path`
+    fn output(
+        &self,
+        output: &OutputConf,
+        underlayer: Box<dyn UnderlayerConfImpl>,
+    ) -> Result<Self, Error> {
+        let name = fs::path_to_str(&output.path);
+        let source = Source::new(
+            self.source.path.as_path(),
+            self.source.from.as_path(),
+            output.path.as_path(),
+        );
+        let conf = self.conf.output(output, underlayer);
+        // each output has its own cache file, such as `.gen_ui_cache.src_gen_dioxus`
+        let cache_file = format!("{}.{}", CACHE_FILE, name.replace(['/', '\\'], "_"));
+
+        let mut compiler = Self::with_conf(source, conf, &cache_file, Arc::clone(&self.excludes))?;
+        compiler.prune = self.prune;
+        compiler.output = Some(name);
+        Ok(compiler)
+    }
+    fn with_conf(
+        source: Source,
+        conf: GenUIConf,
+        cache_file: &str,
+        excludes: Arc<ExcludeMatcher>,
+    ) -> Result<Self, Error> {
+        // [target] --------------------------------------------------------------------------------------
+        let target = registry::compiler(
+            conf.compiler.target.as_str(),
+            &source,
+            conf.underlayer.target.as_ref(),
+        )?;
         // [cache] ---------------------------------------------------------------------------------------
-        let cache = Cache::new(&source_path)?;
+        let cache = Cache::named(source.from_path(), cache_file)?;
 
         Ok(Self {
             source,
             target,
             conf,
             cache,
+            excludes,
+            incremental: false,
+            stamps: None,
+            diagnostics: Diagnostics::default(),
+            updated: None,
+            metrics: Metrics::default(),
+            daemon: None,
+            prune: true,
+            strict: false,
+            pending: vec![],
+            outputs: vec![],
+            failed: vec![],
+            prepared: true,
+            output: None,
         })
     }
+    /// ## override the compiler settings by CLI flags, see `ConfOverrides`
+    pub fn overrides(&mut self, overrides: &ConfOverrides) -> Result<(), Error> {
+        self.conf.compiler.apply_cli(overrides)?;
+        if !overrides.excludes.is_empty() {
+            self.excludes = Arc::new(self.conf.compiler.excludes.matcher(
+                self.source.from_path(),
+                self.conf.compiler.use_gitignore,
+            )?);
+        }
+        Ok(())
+    }
+    /// ## serve the state of the compiler by JSON-RPC on the address (`--rpc <addr>`)
+    /// see [Daemon] for the address format and methods
+    pub fn serve(&mut self, addr: &str) -> Result<(), Error> {
+        self.daemon
+            .replace(Daemon::listen(addr, self.source.from_path())?);
+        CompilerLogs::Daemon(addr.to_string()).compiler().info();
+        Ok(())
+    }
+    /// ## write the metrics as json to the path after each compile pass
+    pub fn metrics_output<P>(&mut self, path: P)
+    where
+        P: AsRef<Path>,
+    {
+        self.metrics.set_output(path);
+    }
+    /// ## set the callback which is called after each compile pass without errors
+    /// such as restart the app, it is called after the first compile and each update of the watcher
+    pub fn on_updated<F>(&mut self, f: F)
+    where
+        F: FnMut() + 'static,
+    {
+        self.updated.replace(Box::new(f));
+    }
+    fn notify_updated(&mut self) {
+        if let Some(updated) = self.updated.as_mut() {
+            updated();
+        }
+    }
     fn do_compile<P>(&mut self, path: P) -> Result<bool, Error>
     where
         P: AsRef<Path>,
@@ -73,24 +237,66 @@ impl Compiler {
         where
             P: AsRef<Path>,
         {
+            let start = Instant::now();
+            let state = compiler.cache.exists_or_insert(path.as_ref()).unwrap();
             compiler
-                .cache
-                .exists_or_insert(path.as_ref())
-                .unwrap()
-                .modify_then(false, || {
-                    compiler
-                        .target
-                        .compile(path.as_ref().to_path_buf())
-                        .map(|_| true)
-                })
+                .metrics
+                .record(path.as_ref(), Phase::Hash, start.elapsed());
+
+            state.modify_then(false, || {
+                if let Some(daemon) = compiler.daemon.as_ref() {
+                    daemon.begin(path.as_ref());
+                }
+                let start = Instant::now();
+                let res = compiler
+                    .target
+                    .compile(path.as_ref().to_path_buf())
+                    .map(|_| true);
+                compiler
+                    .metrics
+                    .record(path.as_ref(), Phase::Compile, start.elapsed());
+                res
+            })
+        }
+
+        // the file requested by `recompile` is compiled even if it is unchanged
+        if self
+            .daemon
+            .as_ref()
+            .is_some_and(|daemon| daemon.take_forced(path.as_ref()))
+        {
+            self.cache.remove(path.as_ref());
         }
 
         match (path.as_ref().is_file(), path.as_ref().is_gen_file()) {
             (false, true) | (false, false) => {
-                // if is dir, do nothing , use lazy compile(only dir has file, file will be compiled, dir generate after file compiled)
-                Ok(false)
+                // a dir which is moved (renamed) in only has one event, so all files in the dir should be compiled
+                // for a new empty dir, do nothing, use lazy compile(dir generate after file compiled)
+                if path.as_ref().is_dir() {
+                    self.compile_tree(path.as_ref())
+                } else {
+                    Ok(false)
+                }
+            }
+            (true, true) => {
+                let compiled = modify(self, path.as_ref())?;
+                if compiled {
+                    // [update imports and recompile dependents] ----------------------------------------------------
+                    let source_path = self.source.from_path();
+                    let start = Instant::now();
+                    self.cache
+                        .graph_mut()
+                        .update(source_path.as_path(), path.as_ref())?;
+                    let unresolved = DepGraph::unresolved(source_path.as_path(), path.as_ref())?;
+                    self.metrics
+                        .record(path.as_ref(), Phase::Analyze, start.elapsed());
+                    for import in unresolved {
+                        self.warn_unresolved(path.as_ref(), &import);
+                    }
+                    self.compile_dependents(path.as_ref())?;
+                }
+                Ok(compiled)
             }
-            (true, true) => modify(self, path.as_ref()),
             (true, false) => {
                 if path.as_ref().file_name().unwrap() == "main.rs" {
                     modify(self, path.as_ref())
@@ -103,48 +309,53 @@ impl Compiler {
                         false,
                     )?;
 
-                    self.cache
-                        .exists_or_insert(path.as_ref())
-                        .unwrap()
-                        .modify_then(false, || {
-                            copy_file(path.as_ref(), compiled_path).map(|_| false)
-                        })
+                    let start = Instant::now();
+                    let state = self.cache.exists_or_insert(path.as_ref()).unwrap();
+                    self.metrics
+                        .record(path.as_ref(), Phase::Hash, start.elapsed());
+
+                    state.modify_then(false, || {
+                        let start = Instant::now();
+                        let res = copy_file(path.as_ref(), compiled_path).map(|_| false);
+                        self.metrics
+                            .record(path.as_ref(), Phase::Copy, start.elapsed());
+                        res
+                    })
                 }
             }
         }
     }
 
     /// compile all gen / other type file before run compiler
-    fn compile_all(&mut self) -> Result<(), Error> {
-        fn modify<P>(compiler: &mut Compiler, path: P, mut compiled: bool) -> Result<(), Error>
-        where
-            P: AsRef<Path>,
-        {
-            compiler
-                .cache
-                .exists_or_insert(path.as_ref())
-                .unwrap()
-                .then(|_| {
-                    compiled = true;
-                    compiler.target.compile(path.as_ref().to_path_buf())
-                })
-        }
-
-        let mut compiled = false;
+    ///
+    /// `.gen` files are sent to the target compiler when they are changed or their outputs are missing. a builtin
+    /// target gets all of them, because it builds its widget tree and context in memory from them (see
+    /// `registry::needs_full_tree`). other files are only copied when they are changed.
+    /// files which are recorded in the cache but not exist any more will be removed (see `sweep`),
+    /// return the compile pass has no errors or not
+    ///
+    /// ### steps
+    /// 0. sweep orphan outputs unless `prune` is turned off
+    /// 1. walk the source project and collect tasks
+    /// 2. hash, copy and analyze imports on the worker pool (`[compiler] threads`)
+    /// 3. send `.gen` files to the target compiler in walk order (the target compiler is not thread safe)
+    /// 4. write the cache once
+    fn compile_all(&mut self) -> Result<bool, Error> {
         let source_path = self.source.from_path();
-
+        if let Some(daemon) = self.daemon.as_ref() {
+            daemon.begin(source_path.as_path());
+        }
+        // [sweep orphan outputs] ----------------------------------------------------------------------------------------
+        let removed = if self.prune { self.sweep()? } else { 0 };
+        // [collect tasks] -----------------------------------------------------------------------------------------------
+        let mut tasks = vec![];
         for item in WalkDir::new(source_path.as_path())
             .into_iter()
             .filter_map(|e| e.ok())
         {
             let path = item.path().to_path_buf();
             // check if the file or folder is in the exclude list, if true, skip it
-            if self
-                .conf
-                .compiler
-                .excludes
-                .contains(source_path.as_path(), path.as_path())
-            {
+            if self.excludes.is_excluded(path.as_path()) {
                 continue;
             }
 
@@ -152,12 +363,10 @@ impl Compiler {
                 (false, true) | (false, false) => {
                     continue;
                 }
-                (true, true) => {
-                    let _ = modify(self, path.as_path(), compiled)?;
-                }
+                (true, true) => tasks.push(Task::Gen(path)),
                 (true, false) => {
                     if path.file_name().unwrap() == "main.rs" {
-                        let _ = modify(self, path.as_path(), compiled)?;
+                        tasks.push(Task::Main(path));
                     } else {
                         let compiled_path = path.as_path().to_compiled(
                             self.source.path.as_path(),
@@ -165,26 +374,676 @@ impl Compiler {
                             self.source.to.as_path(),
                             false,
                         )?;
+                        tasks.push(Task::Copy(path, compiled_path));
+                    }
+                }
+            }
+        }
+        // [hash, copy and analyze on pool] ------------------------------------------------------------------------------
+        let cache = &self.cache;
+        let prepares = WorkerPool::new(self.conf.compiler.threads).map(&tasks, |task| {
+            let mut timings = vec![];
+            let start = Instant::now();
+            let hash = calc_hash(task.path()).map_err(|e| Error::from(e.to_string()))?;
+            let state = cache.state(task.path(), &hash);
+            timings.push((Phase::Hash, start.elapsed()));
 
-                        let _ = self
-                            .cache
-                            .exists_or_insert(path.as_path())
-                            .unwrap()
-                            .modify_then(false, || {
-                                compiled = true;
-                                copy_file(path.as_path(), compiled_path).map(|_| true)
-                            });
+            let start = Instant::now();
+            let imports = match task {
+                Task::Gen(path) => {
+                    let imports = (
+                        DepGraph::imports(source_path.as_path(), path.as_path())?,
+                        DepGraph::unresolved(source_path.as_path(), path.as_path())?,
+                    );
+                    timings.push((Phase::Analyze, start.elapsed()));
+                    Some(imports)
+                }
+                Task::Main(_) => None,
+                Task::Copy(path, compiled_path) => {
+                    // the copied file may be deleted by user, copy it again
+                    if state.is_modify() || !compiled_path.exists() {
+                        copy_file(path.as_path(), compiled_path.as_path())?;
+                        timings.push((Phase::Copy, start.elapsed()));
                     }
+                    None
                 }
+            };
+            Ok::<_, Error>((hash, state, imports, timings))
+        });
+        // [compile in order] --------------------------------------------------------------------------------------------
+        let full_tree = registry::needs_full_tree(self.conf.compiler.target.as_str());
+        let mut changed = 0_usize;
+        let mut unchanged = 0_usize;
+        for (task, prepare) in tasks.into_iter().zip(prepares) {
+            let compiled = match (&task, prepare) {
+                // unchanged source whose output exists, nothing to compile
+                (Task::Gen(path), Ok(prepare))
+                    if !full_tree
+                        && !prepare.1.is_modify()
+                        && path
+                            .to_compiled_from_source(&self.source)
+                            .is_ok_and(|output| output.exists()) =>
+                {
+                    Ok(prepare)
+                }
+                (Task::Gen(path) | Task::Main(path), Ok(prepare)) => {
+                    let start = Instant::now();
+                    let res = self.target.compile(path.to_path_buf());
+                    self.metrics
+                        .record(path.as_path(), Phase::Compile, start.elapsed());
+                    res.map(|_| prepare)
+                }
+                (_, prepare) => prepare,
+            };
+            // failed files are not written into cache, so they will be compiled again next time
+            let (hash, state, imports, timings) = match compiled {
+                Ok(compiled) => compiled,
+                Err(e) => {
+                    self.diagnose(task.path(), &e);
+                    continue;
+                }
+            };
+
+            for (phase, duration) in timings {
+                self.metrics.record(task.path(), phase, duration);
+            }
+            if let Some((imports, unresolved)) = imports {
+                self.cache.graph_mut().insert(task.path(), imports);
+                for import in unresolved {
+                    self.warn_unresolved(task.path(), &import);
+                }
+            }
+
+            if state.is_modify() {
+                self.cache.insert(task.path(), hash);
+                changed += 1;
+            } else {
+                unchanged += 1;
             }
         }
 
-        if compiled {
+        if changed + removed > 0 {
             let _ = self.cache.write(source_path.as_path());
         }
 
+        self.logger(CompilerLogs::CompileAll {
+            changed,
+            removed,
+            unchanged,
+        })
+        .info();
+
+        Ok(self.finish_pass() == 0)
+    }
+
+    /// ## emit a diagnostic of the compile error and record it in current compile pass
+    fn diagnose<P>(&mut self, path: P, err: &Error)
+    where
+        P: AsRef<Path>,
+    {
+        self.report(Diagnostic::from_error(path, err));
+    }
+
+    /// ## warn the import which can not be found in the source project
+    fn warn_unresolved<P>(&mut self, path: P, import: &str)
+    where
+        P: AsRef<Path>,
+    {
+        let diagnostic = Diagnostic::new(
+            path,
+            Severity::Warning,
+            format!("can not find `.gen` file for import `{}`", import),
+        )
+        .locate_text("import!", import.rsplit("::").next().unwrap_or(import))
+        .with_hint("import should point to a `.gen` file (or a dir with `mod.gen`) in the source project");
+        self.report(diagnostic);
+    }
+
+    /// ## emit the diagnostic and record it, diagnostics of an extra output note the output
+    fn report(&mut self, mut diagnostic: Diagnostic) {
+        if let Some(output) = self.output.as_ref() {
+            diagnostic = diagnostic.with_note(&format!(
+                "in output `{}` ({})",
+                output, self.conf.compiler.target
+            ));
+        }
+        diagnostic.emit(self.conf.compiler.diagnostics);
+        self.diagnostics.push(diagnostic);
+    }
+
+    /// ## logger of the compiler, logs of an extra output start with the output
+    fn logger(&self, log: CompilerLogs) -> CompilerLogger {
+        let mut logger = log.compiler();
+        if let Some(output) = self.output.as_ref() {
+            logger.output = format!("[{}] {}", output, logger.output);
+        }
+        logger
+    }
+
+    /// ## make sure the super project is a workspace which contains the compiled project
+    /// missing `members` and `resolver` are added directly, creating `Cargo.toml` or adding `[workspace]`
+    /// need to be confirmed by the user (return error if the user refuses or there is no terminal).
+    /// comments and formatting of the manifest are kept
+    fn bootstrap_workspace(&self, target_project: &str) -> Result<(), Error> {
+        let path = self.source.path.join("Cargo.toml");
+        let doc = if path.exists() {
+            Some(read_to_doc(path.as_path())?)
+        } else {
+            None
+        };
+        let repairs = workspace::check(doc.as_ref(), target_project)
+            .map_err(|e| Error::from(format!("{}: {}", fs::path_to_str(&path), e)))?;
+        if repairs.is_empty() {
+            return Ok(());
+        }
+        let steps = repairs
+            .iter()
+            .map(|repair| repair.to_string())
+            .collect::<Vec<String>>()
+            .join(", ");
+
+        if repairs.iter().any(Repair::need_confirm) {
+            let confirmed = std::io::stdin().is_terminal()
+                && Confirm::new(
+                    &CompilerLogs::WorkspaceConfirm {
+                        path: path.clone(),
+                        steps: steps.clone(),
+                    }
+                    .to_string(),
+                )
+                .with_default(true)
+                .prompt()
+                .unwrap_or(false);
+
+            if !confirmed {
+                return Err(Error::from(format!(
+                    "{} is not a workspace which contains `{}`, please {}",
+                    fs::path_to_str(&path),
+                    target_project,
+                    steps
+                )));
+            }
+        }
+
+        let mut doc = doc.unwrap_or_default();
+        workspace::repair(&mut doc, &repairs);
+        fs::write(path.as_path(), &doc.to_string())?;
+        self.logger(CompilerLogs::Workspace(steps)).info();
+        Ok(())
+    }
+
+    /// ## run the hook in `[compiler.hooks]`
+    /// - source: the compiled source file, only for `after_file`
+    ///
+    /// failures are logged, they never stop the compiler (watcher)
+    fn run_hook(&self, hook: Hook, source: Option<&Path>) {
+        let Some(command) = self.conf.compiler.hooks.get(hook) else {
+            return;
+        };
+        self.logger(CompilerLogs::Hook(command.to_string())).info();
+
+        let mut vars = HookVars::new(self.source.from_path(), self.source.to_path());
+        if let Some(source) = source {
+            match source.to_compiled(
+                self.source.path.as_path(),
+                self.source.from.as_path(),
+                self.source.to.as_path(),
+                false,
+            ) {
+                Ok(output) => vars = vars.with_file(source, output.as_path()),
+                Err(e) => {
+                    self.logger(CompilerLogs::HookFailed(e.to_string())).error();
+                    return;
+                }
+            }
+        }
+
+        if let Err(e) = self.conf.compiler.hooks.run(hook, &vars) {
+            self.logger(CompilerLogs::HookFailed(e.to_string())).error();
+        }
+    }
+
+    /// ## prepare the compiled project and the target compiler, see `init`
+    fn prepare(&mut self) -> Result<(), Error> {
+        let target_project = self.source.to.to_str().unwrap().to_string();
+        let compiled_path = self.source.path.as_path().join(target_project.as_str());
+        // [check compiler target] ---------------------------------------------------------------------------------------
+        // the super project should be a workspace project which contains the compiled project,
+        // check it before clearing anything, the user may refuse to repair it
+        self.bootstrap_workspace(target_project.as_str())?;
+        // [incremental or clean] ----------------------------------------------------------------------------------------
+        // only trusted cache with an existing compiled project can do incremental compile
+        self.incremental = self.conf.compiler.incremental
+            && self.cache.is_trusted()
+            && compiled_path.join("Cargo.toml").exists();
+
+        if self.incremental {
+            self.stamps = Some(OutputStamps::new(compiled_path.as_path()));
+        } else {
+            // [clear cache] ---------------------------------------------------------------------------------------------
+            let _ = self.cache.clear(self.source.from_path().as_path());
+            // [delete compiled project] ---------------------------------------------------------------------------------
+            if compiled_path.as_path().exists() {
+                fs::delete_dir(compiled_path.as_path())?;
+            }
+        }
+        self.logger(CompilerLogs::Incremental(self.incremental)).info();
+        // [create compiled project] -------------------------------------------------------------------------------------
+        // check the target project exists or not
+        if !compiled_path.exists() {
+            // use std::process::Command to create a new rust project
+            let status = Command::new("cargo")
+                .args(["new", "--bin", target_project.as_str(), "--vcs", "none"])
+                .current_dir(self.source.path.as_path())
+                .status()
+                .map_err(|e| {
+                    Error::from(format!(
+                        "failed to create compiled project `{}`: {}",
+                        target_project, e
+                    ))
+                })?;
+
+            if !status.success() {
+                return Err(Error::from(format!(
+                    "failed to create compiled project `{}` by `cargo new`: {}",
+                    target_project, status
+                )));
+            }
+        }
+        // [before all hook] ---------------------------------------------------------------------------------------------
+        self.run_hook(Hook::BeforeAll, None);
+        // [send plugins] ------------------------------------------------------------------------------------------------
+        self.send_plugins()?;
+        // [target init] -------------------------------------------------------------------------------------------------
+        self.target.init()
+    }
+
+    /// ## first compile of the extra outputs, the outputs which failed are recorded
+    fn first_compile_outputs(&mut self) {
+        for output in self.outputs.iter_mut() {
+            let name = output.output.clone().unwrap_or_default();
+            match output.first_compile() {
+                Ok(true) => {}
+                Ok(false) => self.failed.push(name),
+                Err(e) => {
+                    CompilerLogs::OutputFailed {
+                        output: name.clone(),
+                        reason: e.to_string(),
+                    }
+                    .compiler()
+                    .error();
+                    self.failed.push(name);
+                }
+            }
+        }
+    }
+
+    /// ## compile all files and update the compiled project before watching
+    /// return the compile pass has no errors or not
+    fn first_compile(&mut self) -> Result<bool, Error> {
+        if !self.prepared {
+            return Ok(false);
+        }
+        // [loop compile] ------------------------------------------------------------------------------------------------
+        let started = Instant::now();
+        let success = self.compile_all()?;
+        // [do target before compile] ------------------------------------------------------------------------------------
+        let start = Instant::now();
+        self.target.before_compile()?;
+        self.metrics.record_project(Phase::Update, start.elapsed());
+        // [metrics] -----------------------------------------------------------------------------------------------------
+        self.logger(CompilerLogs::Metrics {
+            total: ms(&started.elapsed()),
+            phases: self.metrics.pass_phases(),
+            slowest: self.metrics.slowest(self.source.from_path(), 3),
+        })
+        .info();
+        self.finish_metrics();
+        // [restore unchanged outputs] -----------------------------------------------------------------------------------
+        if let Some(stamps) = self.stamps.take() {
+            let _ = stamps.restore();
+        }
+        self.write_source_map();
+        // [after all hook] ----------------------------------------------------------------------------------------------
+        self.run_hook(Hook::AfterAll, None);
+        Ok(success)
+    }
+
+    /// ## compile all files once without watching (`ract build`)
+    /// return error if any file failed to compile
+    pub fn build(&mut self) -> Result<(), Error> {
+        self.init()?;
+        let success = self.first_compile();
+        self.first_compile_outputs();
+        // a failed `[compiler] target` is reported with the failed outputs
+        if !success? && self.prepared {
+            Err(Error::from(format!(
+                "failed to compile {}",
+                self.source.from_path().display()
+            )))
+        } else if !self.failed.is_empty() {
+            Err(Error::from(format!(
+                "failed to compile {} into outputs: {}",
+                self.source.from_path().display(),
+                self.failed.join(", ")
+            )))
+        } else {
+            Ok(())
+        }
+    }
+
+    /// ## write the source map of the compiled project, see [SourceMap]
+    /// failure is only logged, cargo diagnostics are not mapped then
+    fn write_source_map(&self) {
+        if let Err(e) =
+            SourceMap::build(&self.source, self.cache.sources()).write(self.source.to_path())
+        {
+            self.logger(CompilerLogs::SourceMapFailed(e.to_string())).warn();
+        }
+    }
+
+    /// ## finish the metrics of current compile pass
+    /// write the metrics if `--metrics` is set, failure is only logged
+    fn finish_metrics(&mut self) {
+        if let Err(e) = self.metrics.finish_pass() {
+            self.logger(CompilerLogs::MetricsFailed(e.to_string())).warn();
+        }
+    }
+
+    /// ## finish current compile pass
+    /// log the summary of diagnostics and clear them, return the number of errors
+    fn finish_pass(&mut self) -> usize {
+        let (errors, warnings) = self.diagnostics.count();
+        let logger = self.logger(CompilerLogs::Summary { errors, warnings });
+        if errors > 0 {
+            logger.error();
+        } else if warnings > 0 {
+            logger.warn();
+        } else {
+            logger.info();
+        }
+        if let Some(daemon) = self.daemon.as_ref() {
+            daemon.end(&self.diagnostics, &self.cache);
+        }
+        self.diagnostics.clear();
+        errors
+    }
+
+    /// ## compile all files in the dir
+    /// errors of each file are reported as diagnostics, so one failed file does not stop others
+    fn compile_tree<P>(&mut self, path: P) -> Result<bool, Error>
+    where
+        P: AsRef<Path>,
+    {
+        let files = WalkDir::new(path.as_ref())
+            .sort_by_file_name()
+            .into_iter()
+            .filter_map(|e| e.ok())
+            .filter(|e| e.file_type().is_file() && !self.excludes.is_excluded(e.path()))
+            .map(|e| e.path().to_path_buf())
+            .collect::<Vec<PathBuf>>();
+
+        let mut compiled = false;
+        for file in files {
+            match self.do_compile(file.as_path()) {
+                Ok(res) => compiled |= res,
+                Err(e) => self.diagnose(file.as_path(), &e),
+            }
+        }
+        Ok(compiled)
+    }
+
+    /// ## remove compiled files and cache of the file or dir
+    /// for a dir, every source recorded in the cache under the dir is removed one by one,
+    /// because `.gen` files are compiled into `src` of the compiled project but other files are not.
+    /// return the removed sources
+    fn remove_tree<P>(&mut self, path: P) -> Result<Vec<PathBuf>, Error>
+    where
+        P: AsRef<Path>,
+    {
+        let mut sources = self
+            .cache
+            .sources()
+            .into_iter()
+            .filter(|source| source.starts_with(path.as_ref()))
+            .collect::<Vec<PathBuf>>();
+        sources.sort();
+        // not recorded in the cache, let target compiler remove it
+        if sources.is_empty() {
+            sources.push(path.as_ref().to_path_buf());
+        }
+
+        let mut removes = vec![];
+        for source in sources {
+            let compiled_path = source.as_path().to_compiled(
+                self.source.path.as_path(),
+                self.source.from.as_path(),
+                self.source.to.as_path(),
+                true,
+            )?;
+            if compiled_path.exists() {
+                removes.extend(self.target.remove(source.to_path_buf())?.unwrap_or_default());
+            }
+            self.cache.remove(source.as_path());
+            removes.push(source);
+        }
+        // [remove empty dirs in compiled project] ---------------------------------------------------------------------
+        let compiled_dir = self.source.to_path().join(
+            path.as_ref()
+                .strip_prefix(self.source.from_path())
+                .map_err(|e| Error::from(e.to_string()))?,
+        );
+        let compiled_src_dir = self.source.to_path().join("src").join(
+            path.as_ref()
+                .strip_prefix(self.source.from_path())
+                .map_err(|e| Error::from(e.to_string()))?,
+        );
+        for dir in [compiled_dir, compiled_src_dir] {
+            if dir.is_dir() {
+                for item in WalkDir::new(dir.as_path())
+                    .contents_first(true)
+                    .into_iter()
+                    .filter_map(|e| e.ok())
+                    .filter(|e| e.file_type().is_dir())
+                {
+                    // only empty dir can be removed
+                    let _ = std::fs::remove_dir(item.path());
+                }
+            }
+        }
+
+        removes.sort();
+        removes.dedup();
+        Ok(removes)
+    }
+
+    /// ## recompile the `.gen` files which import the file (directly or indirectly)
+    /// dependents are compiled in topological order, see [DepGraph::dependents](super::DepGraph::dependents)
+    fn compile_dependents<P>(&mut self, path: P) -> Result<(), Error>
+    where
+        P: AsRef<Path>,
+    {
+        for dependent in self.cache.graph().dependents(path.as_ref()) {
+            if dependent.is_file() {
+                // a failed dependent should not stop other dependents
+                let start = Instant::now();
+                let res = self.target.compile(dependent.to_path_buf());
+                let duration = start.elapsed();
+                self.metrics
+                    .record(dependent.as_path(), Phase::Compile, duration);
+                match res {
+                    Ok(_) => self
+                        .logger(CompilerLogs::Compiled(dependent, ms(&duration)))
+                        .info(),
+                    Err(e) => self.diagnose(dependent.as_path(), &e),
+                }
+            }
+        }
         Ok(())
     }
+
+    /// ## handle a change from the watcher in this compiler and the extra outputs
+    /// every output compiles the change by itself, errors are reported by the output and never stop others
+    fn on_change(&mut self, path: &Path, change: Change) {
+        // a removed dir is not a dir on the disk any more, the cache knows it was one
+        if matches!(change, Change::Remove)
+            && self
+                .excludes
+                .is_excluded_as(path, self.cache.has_dir(path))
+        {
+            return;
+        }
+        if self.prepared {
+            self.apply(path, change);
+        }
+        for output in self.outputs.iter_mut() {
+            output.apply(path, change);
+        }
+    }
+
+    fn apply(&mut self, path: &Path, change: Change) {
+        let res = match change {
+            Change::Compile => self.do_compile(path),
+            Change::Remove => self.remove(path.to_path_buf()).map(|_| true),
+            Change::Ignore => Ok(false),
+        };
+
+        match res {
+            Ok(compiled) => {
+                if compiled {
+                    let source_path = self.source.from_path();
+                    let _ = self.cache.write(source_path.as_path());
+                    let start = Instant::now();
+                    if let Err(e) = self.update() {
+                        self.diagnose(path, &e);
+                    }
+                    self.metrics
+                        .record_project(Phase::Update, start.elapsed());
+                    self.logger(CompilerLogs::Compiled(
+                        path.to_path_buf(),
+                        format!(
+                            "{} ({})",
+                            ms(&self.metrics.pass_total()),
+                            self.metrics.pass_phases()
+                        ),
+                    ))
+                    .info();
+                    self.finish_metrics();
+                    self.write_source_map();
+                    let errors = self.finish_pass();
+                    // [hooks] ------------------------------------------------------------------------------------
+                    // removed file (or dir) has no output
+                    if path.is_file() {
+                        self.run_hook(Hook::AfterFile, Some(path));
+                    }
+                    self.run_hook(Hook::AfterAll, None);
+                    if errors == 0 {
+                        self.notify_updated();
+                    }
+                } else {
+                    // nothing is compiled, the time of hash (or copy) is not a part of the next pass
+                    self.metrics.discard_pass();
+                }
+            }
+            Err(e) => {
+                // copying a file or reading the cache can fail before the pass is started,
+                // `compile/finished` is always paired with `compile/started`
+                if let Some(daemon) = self.daemon.as_ref() {
+                    daemon.begin(path);
+                }
+                self.diagnose(path, &e);
+                self.finish_metrics();
+                let _ = self.finish_pass();
+            }
+        }
+    }
+
+    /// ## remove compiled outputs and cache entries of the sources which do not exist any more
+    /// sources may be deleted or renamed while the watcher is not running, so the sources recorded in the
+    /// cache are compared with the source project before the first compile. each pruned source is logged,
+    /// dirs which become empty in the compiled project are removed too.
+    ///
+    /// return the number of pruned sources
+    fn sweep(&mut self) -> Result<usize, Error> {
+        let mut orphans = self
+            .cache
+            .sources()
+            .into_iter()
+            .filter(|path| !path.exists())
+            .collect::<Vec<PathBuf>>();
+        orphans.sort();
+
+        let compiled_root = self.source.to_path();
+        for path in orphans.iter() {
+            let compiled_path = path.as_path().to_compiled(
+                self.source.path.as_path(),
+                self.source.from.as_path(),
+                self.source.to.as_path(),
+                true,
+            )?;
+            if compiled_path.exists() {
+                // `.gen` outputs are removed by the target compiler which also updates the module tree
+                if path.extension().is_some_and(|ext| ext == "gen") {
+                    let _ = self.target.remove(path.to_path_buf())?;
+                }
+                if compiled_path.is_file() {
+                    std::fs::remove_file(compiled_path.as_path()).map_err(|e| e.to_string())?;
+                }
+                remove_empty_dirs(compiled_path.as_path(), compiled_root.as_path());
+            }
+            self.cache.remove(path.as_path());
+            self.logger(CompilerLogs::Pruned {
+                source: path.to_path_buf(),
+                output: compiled_path,
+            })
+            .info();
+        }
+
+        Ok(orphans.len())
+    }
+}
+
+/// remove the empty parent dirs of the removed file, stop at the root (which is never removed)
+fn remove_empty_dirs(path: &Path, root: &Path) {
+    let mut dir = path.parent();
+    while let Some(current) = dir {
+        if current == root
+            || !current.starts_with(root)
+            || !std::fs::read_dir(current).is_ok_and(|mut entries| entries.next().is_none())
+        {
+            break;
+        }
+        let _ = std::fs::remove_dir(current);
+        dir = current.parent();
+    }
+}
+
+/// change of a path from the watcher, see `Compiler::on_change`
+#[derive(Debug, Clone, Copy)]
+enum Change {
+    /// created or modified, compile it
+    Compile,
+    /// removed (or renamed from), remove its outputs
+    Remove,
+    Ignore,
+}
+
+/// task of `compile_all`
+enum Task {
+    /// `.gen` file, compile by target compiler
+    Gen(PathBuf),
+    /// `main.rs`, send to target compiler
+    Main(PathBuf),
+    /// other files, copy from source to compiled path
+    Copy(PathBuf, PathBuf),
+}
+
+impl Task {
+    fn path(&self) -> &Path {
+        match self {
+            Task::Gen(path) | Task::Main(path) | Task::Copy(path, _) => path.as_path(),
+        }
+    }
 }
 
 impl CompilerImpl for Compiler {
@@ -206,196 +1065,163 @@ impl CompilerImpl for Compiler {
     /// ## check if the generate rust project exists, if not create one
     ///
     /// ### details
+    /// - check whether the super project is a rust workspace project (before anything is deleted)
+    ///     - if not, repair the workspace Cargo.toml after the user confirms, otherwise return an error
+    ///     - if true, check and add the "src_gen" project to the workspace member list
+    /// - check the cache is trusted and incremental compile is enabled
+    ///     - true: keep the cache and the compiled project
+    ///     - false: clear the cache and delete the compiled project
     /// - check if the project exists which named "src_gen"
     ///     - true: return true
     ///     - false: create a new rust project named "src_gen"
-    /// - and need to check whether the super project is a rust workspace project
-    ///     - if not, panic and tell the user to create a workspace project
-    ///     - if true, check and add the "src_gen" project to the workspace member list
     /// ### test
     /// - no src_gen: 👌
     /// - no src_gen and no workspace: 👌
     fn init(&mut self) -> Result<(), Error> {
         // [display LOGO] ------------------------------------------------------------------------------------------------
-        if self.conf.compiler.logo {
+        if self.conf.compiler.logo && !crate::log::compiler::has_sink() {
             println!("{}", CompilerLogs::Logo.to_string());
         }
         // [init logger] -------------------------------------------------------------------------------------------------
         let log_level = self.conf.compiler.log_level;
         let _ = crate::log::compiler::init(log_level);
-        // [clear cache] -------------------------------------------------------------------------------------------------
-        let _ = self.cache.clear(self.source.from_path().as_path());
-        // [delete compiled project] -------------------------------------------------------------------------------------
-        let target_project = self.source.to.to_str().unwrap().to_string();
-        let compiled_path = self.source.path.as_path().join(target_project.as_str());
-
-        if compiled_path.as_path().exists() {
-            fs::delete_dir(compiled_path.as_path())?;
+        // [check conf] --------------------------------------------------------------------------------------------------
+        for warning in self.conf.warnings.iter() {
+            CompilerLogs::ConfWarning(warning.to_string()).compiler().warn();
         }
-        // [check compiler target] ---------------------------------------------------------------------------------------
-        // check the super project is a workspace project or not
-        let workspace_toml_path = self.source.path.join("Cargo.toml");
-
-        if !workspace_toml_path.exists() {
-            return Err(Error::from("Cargo.toml not found in the super project, you should create a workspace project first"));
-        } else {
-            // read the super project's Cargo.toml file and check the workspace member list
-            let mut workspace_toml = read_to_doc(workspace_toml_path.as_path())?;
-
-            let member_list = workspace_toml
-                .get_mut("workspace")
-                .expect("workspace not found in Cargo.toml")
-                .get_mut("members")
-                .expect("members not found in Cargo.toml")
-                .as_array_mut()
-                .expect("members is not an array");
-
-            // check member list contains the src_gen project or not
-            if member_list
-                .iter()
-                .find(|item| item.as_str().unwrap() == &target_project)
-                .is_none()
-            {
-                // add the src_gen project to the workspace member list
-                member_list.push(&target_project);
+        if self.strict {
+            self.conf.strict()?;
+        }
+        // [prepare] -----------------------------------------------------------------------------------------------------
+        // a failed `[compiler] target` is recorded like a failed output when there are extra outputs
+        let prepared = match self.prepare() {
+            Ok(_) => None,
+            Err(e) if !self.pending.is_empty() => {
+                let output = fs::path_to_str(&self.source.to);
+                CompilerLogs::OutputFailed {
+                    output: output.clone(),
+                    reason: e.to_string(),
+                }
+                .compiler()
+                .error();
+                self.failed.push(output);
+                self.prepared = false;
+                Some(e)
             }
-
-            // check workspace resolver exists or not, if not, add workspace.resolver = "2"
-            if workspace_toml
-                .get("workspace")
-                .unwrap()
-                .get("resolver")
-                .is_none()
-            {
-                workspace_toml["workspace"]["resolver"] = value("2");
+            Err(e) => return Err(e),
+        };
+        // [outputs] -----------------------------------------------------------------------------------------------------
+        // a failed output is logged and skipped, it never stops this compiler and other outputs
+        for (output, underlayer) in std::mem::take(&mut self.pending) {
+            let res = self.output(&output, underlayer).and_then(|mut compiler| {
+                compiler.prepare()?;
+                Ok(compiler)
+            });
+            match res {
+                Ok(compiler) => self.outputs.push(compiler),
+                Err(e) => {
+                    let output = fs::path_to_str(&output.path);
+                    CompilerLogs::OutputFailed {
+                        output: output.to_string(),
+                        reason: e.to_string(),
+                    }
+                    .compiler()
+                    .error();
+                    self.failed.push(output);
+                }
             }
-
-            // write back
-            fs::write(workspace_toml_path.as_path(), &workspace_toml.to_string())
-                .expect("failed to write super project's Cargo.toml");
         }
-
-        // check the target project exists or not
-        if !compiled_path.exists() {
-            // use std::process::Command to create a new rust project
-            let status = Command::new("cargo")
-                .args(["new", "--bin", target_project.as_str(), "--vcs", "none"])
-                .current_dir(self.source.path.as_path())
-                .status()
-                .expect("failed to create target project");
-
-            if !status.success() {
-                panic!("failed to create target project");
-            }
+        match prepared {
+            // nothing is left to compile
+            Some(e) if self.outputs.is_empty() => Err(e),
+            _ => Ok(()),
         }
-        // [send plugins] ------------------------------------------------------------------------------------------------
-        self.send_plugins()?;
-        // [target init] -------------------------------------------------------------------------------------------------
-        self.target.init()
     }
 
     fn before_compile(&mut self) -> Result<(), Error> {
-        // [loop compile] ------------------------------------------------------------------------------------------------
-        let _ = self.compile_all()?;
-        // [do target before compile] ------------------------------------------------------------------------------------
-        self.target.before_compile()
+        let success = self.first_compile();
+        self.first_compile_outputs();
+        if success? {
+            self.notify_updated();
+        }
+        Ok(())
     }
 
     fn after_compile(&mut self) -> Result<(), Error> {
         self.target.after_compile()
     }
 
+    /// ## remove the file or dir
+    /// a removed (or renamed) dir is handled as a subtree, see `remove_tree`
     fn remove(&mut self, path: PathBuf) -> Result<Option<Vec<PathBuf>>, Error> {
-        self.target.remove(path).map(|removes| {
-            if let Some(removes) = removes {
-                for path in removes {
-                    // dbg!(path.as_path());
-                    self.cache.remove(path.as_path());
-                }
-            }
-            None
-        })
+        if let Some(daemon) = self.daemon.as_ref() {
+            daemon.begin(path.as_path());
+        }
+        let removes = self.remove_tree(path.as_path())?;
+        for path in removes.iter() {
+            // dbg!(path.as_path());
+            self.cache.remove(path.as_path());
+        }
+        // files which import the removed files should be recompiled
+        for path in removes.iter() {
+            self.compile_dependents(path.as_path())?;
+        }
+        Ok(None)
     }
 
     fn compile(&mut self, _path: PathBuf) -> Result<(), Error> {
-        fn handle<P>(
-            compiler: &mut Compiler,
-            path: P,
-            res: Result<bool, Error>,
-        ) -> Result<(), Error>
-        where
-            P: AsRef<Path>,
-        {
-            match res {
-                Ok(compiled) => {
-                    if compiled {
-                        let source_path = compiler.source.from_path();
-                        let _ = compiler.cache.write(source_path.as_path());
-                        CompilerLogs::Compiled(path.as_ref().to_path_buf())
-                            .compiler()
-                            .info();
-                        let _ = compiler.update()?;
-                    }
-                }
-                Err(e) => {
-                    CompilerLogger::new(&e.to_string()).error();
-                }
-            }
-            Ok(())
-        }
-
         // [compiler source path] -------------------------------------------------------------------------
         let source = self.source.from_path();
         // [init watcher] ---------------------------------------------------------------------------------
-        let excludes = self.conf.compiler.excludes.clone();
+        let excludes = Arc::clone(&self.excludes);
+        let (tx, rx) = channel();
+        if let Some(daemon) = self.daemon.as_ref() {
+            daemon.attach(tx.clone());
+        }
+
+        let watcher = self.conf.compiler.watcher;
 
         #[cfg(not(target_os = "macos"))]
-        let _ = init_watcher(source, &excludes, |path, event| {
-            let res = match event {
+        let _ = init_watcher(source, excludes.as_ref(), &watcher, (tx, rx), |path, event| {
+            let change = match event {
                 notify::EventKind::Modify(kind) => match kind {
-                    notify::event::ModifyKind::Name(rename_mode) => {
-                        if let notify::event::RenameMode::From = *rename_mode {
-                            self.remove(path.to_path_buf()).map(|_| true)
+                    // rename events may be `From`, `To`, `Both` or `Any` on different platforms,
+                    // use the path state to know it is the old path or the new path
+                    notify::event::ModifyKind::Name(_) => {
+                        if path.exists() {
+                            Change::Compile
                         } else {
-                            self.do_compile(path)
+                            Change::Remove
                         }
                     }
-                    _ => self.do_compile(path),
+                    _ => Change::Compile,
                 },
-                notify::EventKind::Create(_) => {
-                    self.do_compile(path)
-                }
-                notify::EventKind::Remove(_) => {
-                    self.remove(path.to_path_buf()).map(|_| true)
-                }
-                _ => {
-                    Ok(false)
-                }
+                notify::EventKind::Create(_) => Change::Compile,
+                notify::EventKind::Remove(_) => Change::Remove,
+                _ => Change::Ignore,
             };
-            handle(self, path, res)
+            self.on_change(path, change);
+            Ok(())
         });
 
         #[cfg(target_os = "macos")]
-        let _ = init_watcher(source, &excludes, |path, state| {
-            let res = match state {
-                fs::FileState::Modified | fs::FileState::Created => self.do_compile(path),
-                fs::FileState::Deleted => {
-                    // eprintln!("remove file: {:?}", path);
-                    self.remove(path.to_path_buf()).map(|_| true)
-                }
+        let _ = init_watcher(source, excludes.as_ref(), &watcher, (tx, rx), |path, state| {
+            let change = match state {
+                fs::FileState::Modified | fs::FileState::Created => Change::Compile,
+                fs::FileState::Deleted => Change::Remove,
                 fs::FileState::Renamed => {
                     // find the rename file
                     if path.exists() {
-                        self.do_compile(path)
+                        Change::Compile
                     } else {
                         // remove from cache
-                        self.remove(path.to_path_buf()).map(|_| true)
+                        Change::Remove
                     }
                 }
-                _ => Ok(false),
+                _ => Change::Ignore,
             };
-
-            handle(self, path, res)
+            self.on_change(path, change);
+            Ok(())
         });
 
         Ok(())
@@ -428,3 +1254,23 @@ mod test_walkdir {
         }
     }
 }
+
+#[cfg(test)]
+mod test_sweep {
+    use super::remove_empty_dirs;
+    use crate::common::TempDir;
+
+    #[test]
+    fn empty_dirs() {
+        let tmp = TempDir::new("ract_sweep").unwrap();
+        let root = tmp.path();
+        let dir = root.join("src").join("views").join("items");
+        std::fs::create_dir_all(dir.as_path()).unwrap();
+        std::fs::write(root.join("src").join("main.rs"), "").unwrap();
+
+        remove_empty_dirs(dir.join("card.rs").as_path(), root);
+        // `src` still has `main.rs`
+        assert!(!root.join("src").join("views").exists());
+        assert!(root.join("src").exists());
+    }
+}
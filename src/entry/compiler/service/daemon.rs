@@ -0,0 +1,499 @@
+use std::{
+    fmt::Display,
+    io::{self, BufRead, BufReader, Read, Write},
+    net::{Shutdown, SocketAddr, TcpListener, TcpStream, ToSocketAddrs},
+    path::{Component, Path, PathBuf},
+    sync::{
+        mpsc::{sync_channel, Sender, SyncSender},
+        Arc, Mutex,
+    },
+    thread,
+    time::Duration,
+};
+
+use gen_utils::{common::fs, error::Error};
+use serde_json::{json, Value};
+
+use super::{Cache, Diagnostics, WatchMsg};
+
+/// lines which are not written to a client yet, a client which falls behind more is dropped
+const CLIENT_BACKLOG: usize = 64;
+/// a client which can not receive a line in time is dropped
+const WRITE_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// # Daemon Status
+/// - idle: waiting for file changes
+/// - compiling: a compile pass is running
+/// - error: the last compile pass has errors
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum DaemonStatus {
+    #[default]
+    Idle,
+    Compiling,
+    Error,
+}
+
+impl Display for DaemonStatus {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            DaemonStatus::Idle => "idle",
+            DaemonStatus::Compiling => "compiling",
+            DaemonStatus::Error => "error",
+        })
+    }
+}
+
+/// state shared by the compiler and the connections
+#[derive(Default)]
+struct Shared {
+    status: DaemonStatus,
+    /// finished compile passes
+    passes: usize,
+    errors: usize,
+    warnings: usize,
+    /// diagnostics of the last compile pass
+    diagnostics: Vec<Value>,
+    cache: Value,
+    /// source project, relative paths of `recompile` are joined to it
+    source: PathBuf,
+    /// files which need to be compiled even if they are unchanged
+    forced: Vec<PathBuf>,
+    /// sender of the watcher loop, `None` before watching
+    watcher: Option<Sender<WatchMsg>>,
+    /// connections which receive notifications
+    clients: Vec<Client>,
+}
+
+impl Shared {
+    /// ## send a notification to all connections
+    /// lines are queued to the writer thread of each connection, so the compiler never waits for a client.
+    /// closed connections and connections which fall behind are removed (and closed)
+    fn notify(&mut self, method: &str, params: Value) {
+        let line = json!({"jsonrpc": "2.0", "method": method, "params": params}).to_string();
+        self.clients
+            .retain(|client| client.lines.try_send(line.clone()).is_ok());
+    }
+}
+
+/// a connection which receives notifications, see `connect`
+struct Client {
+    /// queue of the writer thread
+    lines: SyncSender<String>,
+    /// close the connection, called when the client is dropped
+    close: Box<dyn Fn() + Send>,
+}
+
+impl Drop for Client {
+    fn drop(&mut self) {
+        (self.close)();
+    }
+}
+
+/// # Compiler Daemon
+/// serve the state of the compiler by JSON-RPC 2.0 on a local socket (`ract run --rpc <addr>`)
+///
+/// - addr: `7878` or `127.0.0.1:7878` (only loopback address is allowed), `unix:/tmp/ract.sock` (unix only)
+/// - each request, response and notification is a json object in one line
+///
+/// ## Methods
+/// - `status`: `{"status": "idle" | "compiling" | "error", "passes": 1, "errors": 0, "warnings": 0}`
+/// - `diagnostics`: diagnostics of the last compile pass, see `Diagnostic::to_value`
+/// - `recompile`: `{"path": "views/home.gen"}`, compile the file even if it is unchanged
+///   (the path is absolute or relative to the source project)
+/// - `cache_stats`: `{"files": 12, "trusted": true, "version": "0.0.2"}`
+/// - `shutdown`: stop watching, the compiler exits
+///
+/// ## Notifications
+/// - `compile/started`: `{"path": "/path/to/views/home.gen"}`
+/// - `compile/finished`: same as the result of `status`
+pub struct Daemon {
+    shared: Arc<Mutex<Shared>>,
+    /// unix socket file, removed when the daemon is dropped
+    socket: Option<PathBuf>,
+}
+
+impl Daemon {
+    /// ## listen on the address and serve in background threads
+    /// - source: the source project
+    pub fn listen<P>(addr: &str, source: P) -> Result<Self, Error>
+    where
+        P: AsRef<Path>,
+    {
+        let shared = Arc::new(Mutex::new(Shared {
+            source: source.as_ref().to_path_buf(),
+            ..Default::default()
+        }));
+
+        let socket = if let Some(path) = addr.strip_prefix("unix:") {
+            Some(listen_unix(PathBuf::from(path), Arc::clone(&shared))?)
+        } else {
+            let addr = loopback(addr)?;
+            let listener = TcpListener::bind(addr)
+                .map_err(|e| Error::from(format!("can not listen on {}: {}", addr, e)))?;
+            let shared = Arc::clone(&shared);
+            thread::spawn(move || accept(listener.incoming(), shared));
+            None
+        };
+
+        Ok(Self { shared, socket })
+    }
+    /// ## attach the sender of the watcher loop, then `recompile` and `shutdown` can be handled
+    pub fn attach(&self, watcher: Sender<WatchMsg>) {
+        if let Ok(mut shared) = self.shared.lock() {
+            shared.watcher.replace(watcher);
+        }
+    }
+    /// ## a compile pass starts
+    /// `compile/started` is only sent once in a pass
+    pub fn begin<P>(&self, path: P)
+    where
+        P: AsRef<Path>,
+    {
+        if let Ok(mut shared) = self.shared.lock() {
+            if shared.status != DaemonStatus::Compiling {
+                shared.status = DaemonStatus::Compiling;
+                let params = json!({"path": fs::path_to_str(path.as_ref())});
+                shared.notify("compile/started", params);
+            }
+        }
+    }
+    /// ## a compile pass is finished
+    /// record the diagnostics and cache of the pass then send `compile/finished`
+    pub fn end(&self, diagnostics: &Diagnostics, cache: &Cache) {
+        if let Ok(mut shared) = self.shared.lock() {
+            let (errors, warnings) = diagnostics.count();
+            shared.status = if errors > 0 {
+                DaemonStatus::Error
+            } else {
+                DaemonStatus::Idle
+            };
+            shared.passes += 1;
+            shared.errors = errors;
+            shared.warnings = warnings;
+            shared.diagnostics = diagnostics.0.iter().map(|d| d.to_value()).collect();
+            shared.cache = json!({
+                "files": cache.sources().len(),
+                "trusted": cache.is_trusted(),
+                "version": cache.version(),
+            });
+            let params = status(&shared);
+            shared.notify("compile/finished", params);
+        }
+    }
+    /// ## take the file if it is requested by `recompile`
+    /// return true if the file should be compiled even if it is unchanged
+    pub fn take_forced<P>(&self, path: P) -> bool
+    where
+        P: AsRef<Path>,
+    {
+        self.shared.lock().is_ok_and(|mut shared| {
+            let len = shared.forced.len();
+            shared.forced.retain(|forced| forced != path.as_ref());
+            shared.forced.len() != len
+        })
+    }
+}
+
+impl Drop for Daemon {
+    fn drop(&mut self) {
+        if let Some(socket) = self.socket.as_ref() {
+            let _ = std::fs::remove_file(socket);
+        }
+    }
+}
+
+/// ## resolve the tcp address, only loopback address is allowed
+/// a port only address (`7878`) is `127.0.0.1:7878`
+fn loopback(addr: &str) -> Result<SocketAddr, Error> {
+    let addr = if addr.parse::<u16>().is_ok() {
+        format!("127.0.0.1:{}", addr)
+    } else {
+        addr.to_string()
+    };
+    let addr = addr
+        .to_socket_addrs()
+        .map_err(|e| Error::from(format!("invalid rpc address {}: {}", addr, e)))?
+        .find(|addr| addr.ip().is_loopback())
+        .ok_or_else(|| Error::from(format!("rpc address {} is not a loopback address", addr)))?;
+    Ok(addr)
+}
+
+#[cfg(unix)]
+fn listen_unix(path: PathBuf, shared: Arc<Mutex<Shared>>) -> Result<PathBuf, Error> {
+    use std::os::unix::net::UnixListener;
+
+    // socket file left by the last session
+    if path.exists() {
+        std::fs::remove_file(path.as_path()).map_err(|e| e.to_string())?;
+    }
+    let listener = UnixListener::bind(path.as_path()).map_err(|e| {
+        Error::from(format!(
+            "can not listen on {}: {}",
+            fs::path_to_str(&path),
+            e
+        ))
+    })?;
+    thread::spawn(move || accept(listener.incoming(), shared));
+    Ok(path)
+}
+
+#[cfg(not(unix))]
+fn listen_unix(_path: PathBuf, _shared: Arc<Mutex<Shared>>) -> Result<PathBuf, Error> {
+    Err(Error::from(
+        "unix socket is not supported on this platform, use a localhost port",
+    ))
+}
+
+/// a connection which can be cloned for reading, responding and notifying
+trait Stream: Read + Write + Send + Sized + 'static {
+    fn try_clone(&self) -> io::Result<Self>;
+    fn set_write_timeout(&self, timeout: Option<Duration>) -> io::Result<()>;
+    fn shutdown(&self) -> io::Result<()>;
+}
+
+impl Stream for TcpStream {
+    fn try_clone(&self) -> io::Result<Self> {
+        TcpStream::try_clone(self)
+    }
+    fn set_write_timeout(&self, timeout: Option<Duration>) -> io::Result<()> {
+        TcpStream::set_write_timeout(self, timeout)
+    }
+    fn shutdown(&self) -> io::Result<()> {
+        TcpStream::shutdown(self, Shutdown::Both)
+    }
+}
+
+#[cfg(unix)]
+impl Stream for std::os::unix::net::UnixStream {
+    fn try_clone(&self) -> io::Result<Self> {
+        std::os::unix::net::UnixStream::try_clone(self)
+    }
+    fn set_write_timeout(&self, timeout: Option<Duration>) -> io::Result<()> {
+        std::os::unix::net::UnixStream::set_write_timeout(self, timeout)
+    }
+    fn shutdown(&self) -> io::Result<()> {
+        std::os::unix::net::UnixStream::shutdown(self, Shutdown::Both)
+    }
+}
+
+fn accept<S, I>(incoming: I, shared: Arc<Mutex<Shared>>)
+where
+    S: Stream,
+    I: Iterator<Item = io::Result<S>>,
+{
+    for stream in incoming.filter_map(Result::ok) {
+        let shared = Arc::clone(&shared);
+        thread::spawn(move || {
+            let _ = connect(stream, shared);
+        });
+    }
+}
+
+/// ## handle requests of the connection line by line
+/// responses and notifications are written by one writer thread, so that they are not mixed in a line.
+/// a write which times out closes the connection
+fn connect<S>(stream: S, shared: Arc<Mutex<Shared>>) -> io::Result<()>
+where
+    S: Stream,
+{
+    let (lines, rx) = sync_channel::<String>(CLIENT_BACKLOG);
+    let mut writer = stream.try_clone()?;
+    writer.set_write_timeout(Some(WRITE_TIMEOUT))?;
+    thread::spawn(move || {
+        for line in rx {
+            if writeln!(writer, "{}", line)
+                .and_then(|_| writer.flush())
+                .is_err()
+            {
+                let _ = writer.shutdown();
+                break;
+            }
+        }
+    });
+    let closer = stream.try_clone()?;
+    if let Ok(mut shared) = shared.lock() {
+        shared.clients.push(Client {
+            lines: lines.clone(),
+            close: Box::new(move || {
+                let _ = closer.shutdown();
+            }),
+        });
+    }
+
+    for line in BufReader::new(stream).lines() {
+        let line = line?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        if let Some(response) = handle(&line, &shared) {
+            if lines.send(response.to_string()).is_err() {
+                break;
+            }
+        }
+    }
+    Ok(())
+}
+
+fn status(shared: &Shared) -> Value {
+    json!({
+        "status": shared.status.to_string(),
+        "passes": shared.passes,
+        "errors": shared.errors,
+        "warnings": shared.warnings,
+    })
+}
+
+/// ## handle a JSON-RPC request, return the response (`None` for notification)
+fn handle(line: &str, shared: &Mutex<Shared>) -> Option<Value> {
+    let request = match serde_json::from_str::<Value>(line) {
+        Ok(request) => request,
+        Err(e) => return Some(error(Value::Null, -32700, format!("parse error: {}", e))),
+    };
+    let id = request.get("id").cloned();
+    let Some(method) = request.get("method").and_then(Value::as_str) else {
+        return Some(error(id.unwrap_or(Value::Null), -32600, "invalid request"));
+    };
+
+    let res = shared
+        .lock()
+        .map_err(|_| (-32603, "daemon state is poisoned".to_string()))
+        .and_then(|mut shared| call(&mut shared, method, request.get("params")));
+
+    id.map(|id| match res {
+        Ok(result) => json!({"jsonrpc": "2.0", "id": id, "result": result}),
+        Err((code, message)) => error(id, code, message),
+    })
+}
+
+fn call(shared: &mut Shared, method: &str, params: Option<&Value>) -> Result<Value, (i64, String)> {
+    let watcher = |shared: &Shared| {
+        shared
+            .watcher
+            .clone()
+            .ok_or_else(|| (-32000, "the watcher is not running".to_string()))
+    };
+
+    match method {
+        "status" => Ok(status(shared)),
+        "diagnostics" => Ok(Value::Array(shared.diagnostics.clone())),
+        "cache_stats" => Ok(shared.cache.clone()),
+        "recompile" => {
+            let path = params
+                .and_then(|params| params.get("path").or_else(|| params.get(0)))
+                .and_then(Value::as_str)
+                .ok_or_else(|| (-32602, "`path` is required".to_string()))?;
+            let path = Path::new(path);
+            let path = shared.source.join(path).components().collect::<PathBuf>();
+            let outside = path.components().any(|c| matches!(c, Component::ParentDir))
+                || !path.starts_with(shared.source.as_path());
+            if outside || !path.is_file() {
+                return Err((
+                    -32602,
+                    format!(
+                        "{} is not a file in the source project",
+                        fs::path_to_str(&path)
+                    ),
+                ));
+            }
+            let watcher = watcher(shared)?;
+            shared.forced.push(path.clone());
+            watcher
+                .send(WatchMsg::Recompile(path))
+                .map_err(|e| (-32000, e.to_string()))?;
+            Ok(Value::Null)
+        }
+        "shutdown" => {
+            watcher(shared)?
+                .send(WatchMsg::Shutdown)
+                .map_err(|e| (-32000, e.to_string()))?;
+            Ok(Value::Null)
+        }
+        _ => Err((-32601, format!("method not found: {}", method))),
+    }
+}
+
+fn error<M>(id: Value, code: i64, message: M) -> Value
+where
+    M: Display,
+{
+    json!({"jsonrpc": "2.0", "id": id, "error": {"code": code, "message": message.to_string()}})
+}
+
+#[cfg(test)]
+mod test_daemon {
+    use std::sync::{
+        atomic::{AtomicBool, Ordering},
+        mpsc::{channel, sync_channel},
+        Arc, Mutex,
+    };
+
+    use serde_json::json;
+
+    use super::{handle, Client, Shared, WatchMsg};
+    use crate::common::TempDir;
+
+    #[test]
+    fn slow_client() {
+        let (lines, rx) = sync_channel(1);
+        let closed = Arc::new(AtomicBool::new(false));
+        let close = Arc::clone(&closed);
+        let mut shared = Shared {
+            clients: vec![Client {
+                lines,
+                close: Box::new(move || close.store(true, Ordering::SeqCst)),
+            }],
+            ..Default::default()
+        };
+        shared.notify("compile/started", json!({}));
+        assert_eq!(shared.clients.len(), 1);
+        // the client does not read the first line, it is dropped instead of blocking the compiler
+        shared.notify("compile/finished", json!({}));
+        assert!(shared.clients.is_empty());
+        assert!(closed.load(Ordering::SeqCst));
+        assert!(rx.recv().unwrap().contains("compile/started"));
+    }
+
+    #[test]
+    fn requests() {
+        let tmp = TempDir::new("ract_daemon").unwrap();
+        let source = tmp.path();
+        let shared = Mutex::new(Shared {
+            source: source.to_path_buf(),
+            ..Default::default()
+        });
+        let request = |line: &str| handle(line, &shared);
+
+        assert_eq!(
+            request(r#"{"jsonrpc":"2.0","id":1,"method":"status"}"#),
+            Some(json!({"jsonrpc": "2.0", "id": 1, "result": {
+                "status": "idle", "passes": 0, "errors": 0, "warnings": 0
+            }}))
+        );
+        assert_eq!(
+            request(r#"{"jsonrpc":"2.0","id":2,"method":"build"}"#).unwrap()["error"]["code"],
+            -32601
+        );
+        assert_eq!(request("{").unwrap()["error"]["code"], -32700);
+        // notification has no response
+        assert!(request(r#"{"jsonrpc":"2.0","method":"status"}"#).is_none());
+        // watcher is not running
+        assert_eq!(
+            request(r#"{"jsonrpc":"2.0","id":3,"method":"shutdown"}"#).unwrap()["error"]["code"],
+            -32000
+        );
+
+        let (tx, rx) = channel();
+        shared.lock().unwrap().watcher.replace(tx);
+        let file = source.join("home.gen");
+        std::fs::write(file.as_path(), "").unwrap();
+        let recompile = |path: &str| {
+            request(&json!({"jsonrpc": "2.0", "id": 4, "method": "recompile", "params": {"path": path}}).to_string())
+                .unwrap()
+        };
+        assert!(recompile(file.file_name().unwrap().to_str().unwrap())
+            .get("result")
+            .is_some());
+        assert_eq!(recompile("../etc/passwd")["error"]["code"], -32602);
+        assert!(matches!(rx.recv().unwrap(), WatchMsg::Recompile(path) if path == file));
+    }
+}
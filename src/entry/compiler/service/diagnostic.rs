@@ -0,0 +1,353 @@
+use std::{
+    fmt::Display,
+    path::{Path, PathBuf},
+    str::FromStr,
+};
+
+use colored::Colorize;
+use gen_utils::{common::fs, error::Error};
+use log::{error, warn};
+use serde_json::json;
+use toml_edit::{Formatted, Value};
+
+use crate::log::compiler::{send, CompilerEvent};
+
+/// # Diagnostic Format
+/// how diagnostics are emitted, set in `gen_ui.toml`
+/// ```toml
+/// [compiler]
+/// diagnostics = "human"
+/// ```
+/// - human: render like rustc with source excerpt (default)
+/// - json: render like `human` and also print each diagnostic as a json line to stdout (for editors)
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum DiagnosticFormat {
+    #[default]
+    Human,
+    Json,
+}
+
+impl FromStr for DiagnosticFormat {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "human" => Ok(DiagnosticFormat::Human),
+            "json" => Ok(DiagnosticFormat::Json),
+            _ => Err(format!(
+                "unknown diagnostics format: {}, expect `human` or `json`",
+                s
+            )
+            .into()),
+        }
+    }
+}
+
+impl From<&DiagnosticFormat> for Value {
+    fn from(value: &DiagnosticFormat) -> Self {
+        Value::String(Formatted::new(
+            match value {
+                DiagnosticFormat::Human => "human",
+                DiagnosticFormat::Json => "json",
+            }
+            .to_string(),
+        ))
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Severity {
+    Error,
+    Warning,
+}
+
+impl Display for Severity {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            Severity::Error => "error",
+            Severity::Warning => "warning",
+        })
+    }
+}
+
+/// # Diagnostic
+/// a problem found when compiling a file
+///
+/// render as:
+/// ```text
+/// error: can not parse template
+///   --> views/home.gen:3:9
+///    |
+///  3 |         <Hello id="header"></Hello
+///    |         ^^^^^^
+///    = help: check the syntax of the `.gen` file
+/// ```
+#[derive(Debug, Clone)]
+pub struct Diagnostic {
+    pub file: PathBuf,
+    /// line number, start from 1
+    pub line: Option<usize>,
+    /// column number, start from 1
+    pub column: Option<usize>,
+    pub severity: Severity,
+    pub message: String,
+    pub hint: Option<String>,
+    /// extra information, such as the location in the generated code (see `SourceMap`)
+    pub notes: Vec<String>,
+}
+
+impl Diagnostic {
+    pub fn new<P>(file: P, severity: Severity, message: String) -> Self
+    where
+        P: AsRef<Path>,
+    {
+        Self {
+            file: file.as_ref().to_path_buf(),
+            line: None,
+            column: None,
+            severity,
+            message,
+            hint: None,
+            notes: vec![],
+        }
+    }
+    pub fn with_hint(mut self, hint: &str) -> Self {
+        self.hint.replace(hint.to_string());
+        self
+    }
+    pub fn with_note(mut self, note: &str) -> Self {
+        self.notes.push(note.to_string());
+        self
+    }
+    /// ## point the diagnostic to the first place of the text after the anchor in the file
+    pub fn locate_text(mut self, anchor: &str, text: &str) -> Self {
+        if let Ok(content) = std::fs::read_to_string(self.file.as_path()) {
+            if let Some((line, code)) = content
+                .lines()
+                .enumerate()
+                .skip_while(|(_, code)| !code.contains(anchor))
+                .find(|(_, code)| code.contains(text))
+            {
+                self.line.replace(line + 1);
+                self.column
+                    .replace(code[..code.find(text).unwrap_or_default()].chars().count() + 1);
+            }
+        }
+        self
+    }
+    /// ## convert compile error of the file to diagnostic
+    /// errors from the underlayer do not have spans, the location is read from the message if it has one
+    pub fn from_error<P>(file: P, err: &Error) -> Self
+    where
+        P: AsRef<Path>,
+    {
+        let message = err.to_string();
+        let (line, column) = locate(&message);
+        let hint = match err {
+            Error::Parse(_) => Some("check the syntax of the file"),
+            Error::Convert(_) => Some("check the value types used in the file"),
+            Error::Fs(_) => {
+                Some("check the file exists and ract has permission to read or write it")
+            }
+            Error::Env(_) => Some("use `ract check` to check the environment"),
+            _ => None,
+        };
+
+        Self {
+            line,
+            column,
+            hint: hint.map(ToString::to_string),
+            ..Self::new(file, Severity::Error, message)
+        }
+    }
+    /// ## render as rustc style with source excerpt
+    pub fn render(&self) -> String {
+        let (title, caret) = match self.severity {
+            Severity::Error => (
+                self.severity.to_string().bright_red().bold(),
+                "^".bright_red(),
+            ),
+            Severity::Warning => (
+                self.severity.to_string().bright_yellow().bold(),
+                "^".bright_yellow(),
+            ),
+        };
+
+        let mut location = fs::path_to_str(self.file.as_path());
+        if let Some(line) = self.line {
+            location.push_str(&format!(":{}", line));
+            if let Some(column) = self.column {
+                location.push_str(&format!(":{}", column));
+            }
+        }
+
+        let excerpt = self.line.and_then(|line| {
+            std::fs::read_to_string(self.file.as_path())
+                .ok()?
+                .lines()
+                .nth(line.checked_sub(1)?)
+                .map(|code| (line, code.to_string()))
+        });
+        let width = excerpt
+            .as_ref()
+            .map_or(1, |(line, _)| line.to_string().len());
+        let gutter = format!("{} |", " ".repeat(width)).bright_blue();
+
+        let mut res = format!(
+            "{}: {}\n{}{} {}",
+            title,
+            self.message.bold(),
+            " ".repeat(width),
+            "-->".bright_blue(),
+            location
+        );
+
+        if let Some((line, code)) = excerpt {
+            let column = self.column.unwrap_or(1).max(1);
+            let start = code
+                .chars()
+                .take(column - 1)
+                .map(|c| if c == '\t' { '\t' } else { ' ' })
+                .collect::<String>();
+            // mark the word at the column
+            let len = code
+                .chars()
+                .skip(column - 1)
+                .take_while(|c| !c.is_whitespace())
+                .count()
+                .max(1);
+
+            res.push_str(&format!(
+                "\n{}\n{} {}\n{} {}{}",
+                gutter,
+                format!("{} |", line).bright_blue(),
+                code,
+                gutter,
+                start,
+                caret.to_string().repeat(len)
+            ));
+        }
+
+        for note in self.notes.iter() {
+            res.push_str(&format!(
+                "\n{} {} {}",
+                format!("{} =", " ".repeat(width)).bright_blue(),
+                "note:".bold(),
+                note
+            ));
+        }
+        if let Some(hint) = self.hint.as_ref() {
+            res.push_str(&format!(
+                "\n{} {} {}",
+                format!("{} =", " ".repeat(width)).bright_blue(),
+                "help:".bold(),
+                hint
+            ));
+        }
+
+        res
+    }
+    /// ## diagnostic as a json value
+    /// `{"file": "...", "line": 3, "column": 9, "severity": "error", "message": "...", "hint": null, "notes": []}`
+    pub fn to_value(&self) -> serde_json::Value {
+        json!({
+            "file": fs::path_to_str(self.file.as_path()),
+            "line": self.line,
+            "column": self.column,
+            "severity": self.severity.to_string(),
+            "message": self.message,
+            "hint": self.hint,
+            "notes": self.notes,
+        })
+    }
+    /// ## diagnostic as a json line
+    pub fn to_json(&self) -> String {
+        self.to_value().to_string()
+    }
+    /// ## emit diagnostic
+    /// log the rendered diagnostic, and print json line to stdout if format is json
+    /// (the diagnostic is only sent to the sink if it is set, see `log::compiler::set_sink`)
+    pub fn emit(&self, format: DiagnosticFormat) {
+        if send(CompilerEvent::Diagnostic(self)) {
+            return;
+        }
+        match self.severity {
+            Severity::Error => error!("\n{}", self.render()),
+            Severity::Warning => warn!("\n{}", self.render()),
+        }
+
+        if let DiagnosticFormat::Json = format {
+            println!("{}", self.to_json());
+        }
+    }
+}
+
+/// # Diagnostics
+/// diagnostics of a compile pass
+#[derive(Debug, Clone, Default)]
+pub struct Diagnostics(pub Vec<Diagnostic>);
+
+impl Diagnostics {
+    pub fn push(&mut self, diagnostic: Diagnostic) {
+        self.0.push(diagnostic);
+    }
+    pub fn clear(&mut self) {
+        self.0.clear();
+    }
+    /// count of (errors, warnings)
+    pub fn count(&self) -> (usize, usize) {
+        self.0
+            .iter()
+            .fold((0, 0), |(errors, warnings), item| match item.severity {
+                Severity::Error => (errors + 1, warnings),
+                Severity::Warning => (errors, warnings + 1),
+            })
+    }
+}
+
+/// ## find line and column in the message
+/// support `line 3, column 9` and `3:9`
+fn locate(message: &str) -> (Option<usize>, Option<usize>) {
+    fn number_after(message: &str, key: &str) -> Option<usize> {
+        let (_, rest) = message.split_once(key)?;
+        rest.trim_start()
+            .split(|c: char| !c.is_ascii_digit())
+            .next()?
+            .parse()
+            .ok()
+    }
+
+    if let Some(line) = number_after(message, "line ") {
+        let column = number_after(message, "column ").or_else(|| number_after(message, "col "));
+        return (Some(line), column);
+    }
+
+    // `path:3:9` or `3:9`
+    message
+        .split(|c: char| c.is_whitespace() || c == '(' || c == ')' || c == ',')
+        .find_map(|word| {
+            let mut parts = word.rsplitn(3, ':');
+            let column = parts.next()?.parse::<usize>().ok()?;
+            let line = parts.next()?.parse::<usize>().ok()?;
+            Some((Some(line), Some(column)))
+        })
+        .unwrap_or((None, None))
+}
+
+#[cfg(test)]
+mod test_diagnostic {
+    use super::locate;
+
+    #[test]
+    fn location() {
+        assert_eq!(
+            locate("unexpected token at line 3, column 9"),
+            (Some(3), Some(9))
+        );
+        assert_eq!(
+            locate("expected `>` (views/home.gen:12:5)"),
+            (Some(12), Some(5))
+        );
+        assert_eq!(locate("file not found"), (None, None));
+    }
+}
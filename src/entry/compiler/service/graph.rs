@@ -0,0 +1,304 @@
+use std::{
+    collections::{HashMap, HashSet},
+    path::{Path, PathBuf},
+};
+
+use gen_utils::{common::fs, error::Error};
+use toml_edit::{Array, Item, Table, Value};
+
+/// # Dependency Graph
+/// record the imports between `.gen` files, key is the `.gen` file, value is the `.gen` files it imports
+///
+/// imports are written in the `<script>` of a `.gen` file:
+/// ```text
+/// import!{
+///     crate::views::home::*;
+///     crate::components::easy::*;
+/// }
+/// ```
+/// `crate` points to the source project, so `crate::components::easy::*` is `components/easy.gen`
+/// (or `components/easy/mod.gen`)
+#[derive(Debug, Clone, Default)]
+pub struct DepGraph {
+    deps: HashMap<PathBuf, HashSet<PathBuf>>,
+}
+
+impl DepGraph {
+    /// ## read imports of the `.gen` file and update the graph
+    /// - source: the source project path, which is `crate` in imports
+    pub fn update<P>(&mut self, source: P, path: P) -> Result<(), Error>
+    where
+        P: AsRef<Path>,
+    {
+        let deps = Self::imports(source.as_ref(), path.as_ref())?;
+        self.insert(path, deps);
+        Ok(())
+    }
+    /// ## read imports of the `.gen` file
+    /// imports which can not be found in the source project are ignored
+    pub fn imports<P>(source: P, path: P) -> Result<HashSet<PathBuf>, Error>
+    where
+        P: AsRef<Path>,
+    {
+        let content = fs::read(path.as_ref())?;
+        Ok(parse_imports(&content)
+            .iter()
+            .filter_map(|segments| resolve_import(source.as_ref(), segments))
+            .filter(|dep| dep != path.as_ref())
+            .collect())
+    }
+    /// ## imports of the `.gen` file which can not be found in the source project
+    /// return as written without groups, eg: `crate::views::homee`
+    pub fn unresolved<P>(source: P, path: P) -> Result<Vec<String>, Error>
+    where
+        P: AsRef<Path>,
+    {
+        let content = fs::read(path.as_ref())?;
+        Ok(parse_imports(&content)
+            .into_iter()
+            .filter(|segments| resolve_import(source.as_ref(), segments).is_none())
+            .map(|segments| format!("crate::{}", segments.join("::")))
+            .collect())
+    }
+    pub fn insert<P>(&mut self, path: P, deps: HashSet<PathBuf>)
+    where
+        P: AsRef<Path>,
+    {
+        self.deps.insert(path.as_ref().to_path_buf(), deps);
+    }
+    /// remove the file from the graph, the file will not be removed from the deps of other files
+    /// so that the dependents of the file can still be found after the file is removed
+    pub fn remove<P>(&mut self, path: P)
+    where
+        P: AsRef<Path>,
+    {
+        self.deps.remove(path.as_ref());
+    }
+    pub fn clear(&mut self) {
+        self.deps.clear();
+    }
+    pub fn is_empty(&self) -> bool {
+        self.deps.is_empty()
+    }
+    /// ## get all dependents of the file (direct and indirect) in topological order
+    /// a file always comes after the files it imports, the file itself is not included.
+    /// if there is a cycle, files in the cycle are sorted by path
+    pub fn dependents<P>(&self, path: P) -> Vec<PathBuf>
+    where
+        P: AsRef<Path>,
+    {
+        // [collect dependents] -----------------------------------------------------------------------------
+        let mut dependents = HashSet::new();
+        let mut stack = vec![path.as_ref().to_path_buf()];
+        while let Some(current) = stack.pop() {
+            for (file, deps) in self.deps.iter() {
+                if deps.contains(&current)
+                    && file != path.as_ref()
+                    && dependents.insert(file.clone())
+                {
+                    stack.push(file.clone());
+                }
+            }
+        }
+        // [topological sort] -------------------------------------------------------------------------------
+        let mut res = Vec::with_capacity(dependents.len());
+        let mut pending = dependents.into_iter().collect::<Vec<PathBuf>>();
+        pending.sort();
+        while !pending.is_empty() {
+            let (ready, waiting): (Vec<PathBuf>, Vec<PathBuf>) =
+                pending.iter().cloned().partition(|file| {
+                    self.deps
+                        .get(file)
+                        .is_none_or(|deps| deps.iter().all(|dep| !pending.contains(dep)))
+                });
+
+            if ready.is_empty() {
+                // cycle, can not sort any more
+                res.extend(waiting);
+                break;
+            }
+            res.extend(ready);
+            pending = waiting;
+        }
+
+        res
+    }
+}
+
+impl From<&DepGraph> for Item {
+    fn from(value: &DepGraph) -> Self {
+        let mut table = Table::new();
+        for (k, v) in value.deps.iter() {
+            let mut deps = v.iter().map(fs::path_to_str).collect::<Vec<String>>();
+            deps.sort();
+            table.insert(
+                &fs::path_to_str(k),
+                Item::Value(Value::Array(Array::from_iter(deps))),
+            );
+        }
+        Item::Table(table)
+    }
+}
+
+impl TryFrom<&Item> for DepGraph {
+    type Error = Error;
+
+    fn try_from(value: &Item) -> Result<Self, Self::Error> {
+        let table = value
+            .as_table()
+            .ok_or_else(|| Error::from("can not covert deps item"))?;
+
+        let mut deps = HashMap::new();
+        for (k, v) in table.iter() {
+            let items = v
+                .as_array()
+                .ok_or_else(|| Error::from("deps item must be an array"))?
+                .iter()
+                .map(|item| {
+                    item.as_str()
+                        .map(PathBuf::from)
+                        .ok_or_else(|| Error::from("dep must be a string"))
+                })
+                .collect::<Result<HashSet<PathBuf>, Error>>()?;
+
+            deps.insert(PathBuf::from(k), items);
+        }
+
+        Ok(Self { deps })
+    }
+}
+
+/// ## parse imports in `import!{}` of a `.gen` file
+/// return the path segments of each import without `crate`, eg: `crate::views::home::*` -> `["views", "home"]`
+pub fn parse_imports(content: &str) -> Vec<Vec<String>> {
+    let mut imports = vec![];
+    let mut rest = content;
+
+    while let Some(start) = rest.find("import!") {
+        rest = &rest[start + "import!".len()..];
+        let Some(open) = rest.find('{') else {
+            break;
+        };
+        // find the matched `}`
+        let mut depth = 0;
+        let mut close = None;
+        for (index, c) in rest[open..].char_indices() {
+            match c {
+                '{' => depth += 1,
+                '}' => {
+                    depth -= 1;
+                    if depth == 0 {
+                        close = Some(open + index);
+                        break;
+                    }
+                }
+                _ => {}
+            }
+        }
+        let Some(close) = close else {
+            break;
+        };
+
+        for item in rest[open + 1..close].split(';') {
+            let item = item.trim();
+            // only imports from current project (`crate::`) are `.gen` files
+            if let Some(item) = item.strip_prefix("crate::") {
+                // expand group: `a::{b::*, c::C}` -> `a::b::*`, `a::c::C`
+                let (prefix, group) = item
+                    .split_once('{')
+                    .map_or((item, vec![""]), |(prefix, group)| {
+                        (prefix, group.trim_end_matches('}').split(',').collect())
+                    });
+
+                for sub in group {
+                    let full = format!("{}{}", prefix, sub.trim());
+                    let segments = full
+                        .trim_end_matches('*')
+                        .split("::")
+                        .map(|segment| segment.trim().to_string())
+                        .filter(|segment| !segment.is_empty())
+                        .collect::<Vec<String>>();
+                    if !segments.is_empty() {
+                        imports.push(segments);
+                    }
+                }
+            }
+        }
+
+        rest = &rest[close..];
+    }
+
+    imports
+}
+
+/// ## resolve import segments to a `.gen` file
+/// the longest segments which can be found as `a/b.gen` or `a/b/mod.gen` will be used,
+/// because the last segments may be the name of a widget, eg: `crate::views::home::Home`
+fn resolve_import<P>(source: P, segments: &[String]) -> Option<PathBuf>
+where
+    P: AsRef<Path>,
+{
+    (1..=segments.len()).rev().find_map(|len| {
+        let path = segments[..len]
+            .iter()
+            .fold(source.as_ref().to_path_buf(), |acc, segment| {
+                acc.join(segment)
+            });
+
+        [path.with_extension("gen"), path.join("mod.gen")]
+            .into_iter()
+            .find(|file| file.is_file())
+    })
+}
+
+#[cfg(test)]
+mod test_graph {
+    use std::{collections::HashSet, path::PathBuf};
+
+    use super::{parse_imports, DepGraph};
+
+    #[test]
+    fn imports() {
+        let content = r#"
+        <script>
+        import!{
+            crate::views::home::*;
+            crate::components::{easy::*, hello::Hello};
+            makepad_widgets::*;
+        }
+        </script>
+        "#;
+
+        assert_eq!(
+            parse_imports(content),
+            vec![
+                vec!["views".to_string(), "home".to_string()],
+                vec!["components".to_string(), "easy".to_string()],
+                vec![
+                    "components".to_string(),
+                    "hello".to_string(),
+                    "Hello".to_string()
+                ],
+            ]
+        );
+    }
+
+    #[test]
+    fn dependents() {
+        let mut graph = DepGraph::default();
+        let (root, home, easy, hello) = (
+            PathBuf::from("views/root.gen"),
+            PathBuf::from("views/home.gen"),
+            PathBuf::from("components/easy.gen"),
+            PathBuf::from("components/hello.gen"),
+        );
+        graph.insert(&root, HashSet::from([home.clone(), easy.clone()]));
+        graph.insert(&home, HashSet::from([hello.clone()]));
+        graph.insert(&easy, HashSet::new());
+        graph.insert(&hello, HashSet::new());
+
+        assert_eq!(graph.dependents(&hello), vec![home.clone(), root.clone()]);
+        assert_eq!(graph.dependents(&easy), vec![root.clone()]);
+        assert!(graph.dependents(&root).is_empty());
+    }
+}
@@ -0,0 +1,243 @@
+use std::{
+    collections::BTreeMap,
+    fmt::Display,
+    path::{Path, PathBuf},
+    time::Duration,
+};
+
+use gen_utils::{common::fs, error::Error};
+use serde_json::{json, Map, Value};
+
+/// # Compile Phase
+/// - hash: calculate the hash of the file and check the cache
+/// - analyze: read the imports of the `.gen` file
+/// - copy: copy the non-gen file to the compiled project
+/// - compile: compile the file by the target compiler (parse the `.gen` file and generate the code)
+/// - update: update the compiled project after compile (such as `lib.rs`, `app.rs`)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Phase {
+    Hash,
+    Analyze,
+    Copy,
+    Compile,
+    Update,
+}
+
+impl Display for Phase {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            Phase::Hash => "hash",
+            Phase::Analyze => "analyze",
+            Phase::Copy => "copy",
+            Phase::Compile => "compile",
+            Phase::Update => "update",
+        })
+    }
+}
+
+/// # File Metrics
+/// time spent on each phase of a file in the session
+#[derive(Debug, Clone, Default)]
+pub struct FileMetrics {
+    /// times the file is compiled (or copied)
+    pub count: usize,
+    pub phases: BTreeMap<Phase, Duration>,
+}
+
+impl FileMetrics {
+    pub fn total(&self) -> Duration {
+        self.phases.values().sum()
+    }
+}
+
+/// # Compile Metrics
+/// per-phase timings of each file, collected in the whole compiler session
+///
+/// phases which run on the worker pool (hash, analyze, copy) are summed over all workers,
+/// so the sum of phases may be larger than the wall time of `compile_all`.
+/// if the output is set (`ract run --metrics metrics.json`), metrics are written after each compile pass
+#[derive(Debug, Clone, Default)]
+pub struct Metrics {
+    files: BTreeMap<PathBuf, FileMetrics>,
+    /// phases which do not belong to a file (such as update)
+    project: BTreeMap<Phase, Duration>,
+    /// time of each phase in current pass
+    pass: BTreeMap<Phase, Duration>,
+    passes: usize,
+    output: Option<PathBuf>,
+}
+
+impl Metrics {
+    pub fn set_output<P>(&mut self, path: P)
+    where
+        P: AsRef<Path>,
+    {
+        self.output.replace(path.as_ref().to_path_buf());
+    }
+    /// ## record the time of the phase of the file
+    /// the file is counted when it is compiled or copied
+    pub fn record<P>(&mut self, path: P, phase: Phase, duration: Duration)
+    where
+        P: AsRef<Path>,
+    {
+        let file = self.files.entry(path.as_ref().to_path_buf()).or_default();
+        if let Phase::Compile | Phase::Copy = phase {
+            file.count += 1;
+        }
+        *file.phases.entry(phase).or_default() += duration;
+        *self.pass.entry(phase).or_default() += duration;
+    }
+    /// ## record the time of the phase which does not belong to a file
+    pub fn record_project(&mut self, phase: Phase, duration: Duration) {
+        *self.project.entry(phase).or_default() += duration;
+        *self.pass.entry(phase).or_default() += duration;
+    }
+    /// total time of current pass
+    pub fn pass_total(&self) -> Duration {
+        self.pass.values().sum()
+    }
+    /// ## phases of current pass, eg: `hash 2ms, compile 120ms`
+    pub fn pass_phases(&self) -> String {
+        self.pass
+            .iter()
+            .map(|(phase, duration)| format!("{} {}", phase, ms(duration)))
+            .collect::<Vec<String>>()
+            .join(", ")
+    }
+    /// ## the slowest files of the session, eg: `views/home.gen 120ms`
+    pub fn slowest<P>(&self, prefix: P, n: usize) -> String
+    where
+        P: AsRef<Path>,
+    {
+        let mut files = self.files.iter().collect::<Vec<(&PathBuf, &FileMetrics)>>();
+        files.sort_by_key(|(_, file)| std::cmp::Reverse(file.total()));
+        files
+            .into_iter()
+            .take(n)
+            .map(|(path, file)| {
+                format!(
+                    "{} {}",
+                    fs::path_to_str(path.strip_prefix(prefix.as_ref()).unwrap_or(path)),
+                    ms(&file.total())
+                )
+            })
+            .collect::<Vec<String>>()
+            .join(", ")
+    }
+    /// ## finish current pass, write the metrics if the output is set
+    pub fn finish_pass(&mut self) -> Result<(), Error> {
+        self.passes += 1;
+        self.pass.clear();
+        match self.output.as_ref() {
+            Some(output) => fs::write(output.as_path(), &self.to_json()),
+            None => Ok(()),
+        }
+    }
+    /// ## drop current pass without counting it
+    /// the file may not be compiled (such as its hash is unchanged), the time of the pass is kept in the
+    /// session but not shown in the next pass
+    pub fn discard_pass(&mut self) {
+        self.pass.clear();
+    }
+    /// ## metrics as json
+    /// ```json
+    /// {
+    ///   "ract": "0.2.0",
+    ///   "passes": 2,
+    ///   "phases": { "hash": 1.2, "compile": 120.5, "update": 10.1 },
+    ///   "files": {
+    ///     "/path/to/views/home.gen": { "count": 2, "phases": { "hash": 0.3, "compile": 80.2 } }
+    ///   }
+    /// }
+    /// ```
+    /// durations are in milliseconds
+    pub fn to_json(&self) -> String {
+        let mut phases = self.project.clone();
+        let mut files = Map::new();
+        for (path, file) in self.files.iter() {
+            for (phase, duration) in file.phases.iter() {
+                *phases.entry(*phase).or_default() += *duration;
+            }
+            files.insert(
+                fs::path_to_str(path),
+                json!({
+                    "count": file.count,
+                    "phases": phases_json(&file.phases),
+                }),
+            );
+        }
+
+        serde_json::to_string_pretty(&json!({
+            "ract": env!("CARGO_PKG_VERSION"),
+            "passes": self.passes,
+            "phases": phases_json(&phases),
+            "files": files,
+        }))
+        .unwrap_or_default()
+    }
+}
+
+fn phases_json(phases: &BTreeMap<Phase, Duration>) -> Value {
+    Value::Object(
+        phases
+            .iter()
+            .map(|(phase, duration)| (phase.to_string(), json!(duration.as_secs_f64() * 1000.0)))
+            .collect(),
+    )
+}
+
+/// format duration as milliseconds
+pub fn ms(duration: &Duration) -> String {
+    format!("{}ms", duration.as_millis())
+}
+
+#[cfg(test)]
+mod test_metrics {
+    use std::time::Duration;
+
+    use super::{Metrics, Phase};
+
+    #[test]
+    fn record() {
+        let mut metrics = Metrics::default();
+        metrics.record("/p/views/home.gen", Phase::Hash, Duration::from_millis(2));
+        metrics.record(
+            "/p/views/home.gen",
+            Phase::Compile,
+            Duration::from_millis(100),
+        );
+        metrics.record(
+            "/p/views/root.gen",
+            Phase::Compile,
+            Duration::from_millis(30),
+        );
+        metrics.record_project(Phase::Update, Duration::from_millis(10));
+
+        assert_eq!(metrics.pass_total(), Duration::from_millis(142));
+        assert_eq!(
+            metrics.pass_phases(),
+            "hash 2ms, compile 130ms, update 10ms"
+        );
+        assert_eq!(metrics.slowest("/p", 1), "views/home.gen 102ms");
+
+        metrics.finish_pass().unwrap();
+        assert_eq!(metrics.pass_total(), Duration::ZERO);
+        assert!(metrics.to_json().contains("\"passes\": 1"));
+    }
+
+    #[test]
+    fn discard() {
+        let mut metrics = Metrics::default();
+        metrics.record("/p/views/home.gen", Phase::Hash, Duration::from_millis(2));
+        metrics.discard_pass();
+        assert_eq!(metrics.pass_total(), Duration::ZERO);
+
+        metrics.record(
+            "/p/views/root.gen",
+            Phase::Compile,
+            Duration::from_millis(30),
+        );
+        assert_eq!(metrics.pass_phases(), "compile 30ms");
+        assert!(metrics.to_json().contains("\"passes\": 0"));
+    }
+}
@@ -3,11 +3,25 @@ mod watcher;
 #[cfg(target_os = "macos")]
 mod tracker;
 mod cache;
+mod stamp;
+mod graph;
+mod pool;
+mod diagnostic;
+mod metrics;
+mod daemon;
+mod source_map;
 // 暂时不需要
 // mod context;
 
 pub use compiler::Compiler;
 pub use watcher::*;
 pub use cache::*;
+pub use stamp::OutputStamps;
+pub use graph::DepGraph;
+pub use pool::WorkerPool;
+pub use diagnostic::*;
+pub use metrics::{Metrics, Phase};
+pub use daemon::{Daemon, DaemonStatus};
+pub use source_map::{SourceLocation, SourceMap, SOURCE_MAP};
 #[cfg(target_os = "macos")]
 pub use tracker::*;
\ No newline at end of file
@@ -0,0 +1,82 @@
+use std::{
+    num::NonZeroUsize,
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Mutex,
+    },
+    thread,
+};
+
+/// # Worker Pool
+/// run independent jobs on a fixed number of threads, the results keep the same order as the jobs
+///
+/// the pool is used by `compile_all` to hash, copy and analyze files before they are sent to the target compiler
+#[derive(Debug, Clone, Copy)]
+pub struct WorkerPool {
+    threads: usize,
+}
+
+impl WorkerPool {
+    /// ## new pool
+    /// - threads: number of workers, `0` means use the available parallelism of the machine
+    pub fn new(threads: usize) -> Self {
+        let threads = if threads == 0 {
+            thread::available_parallelism()
+                .map(NonZeroUsize::get)
+                .unwrap_or(1)
+        } else {
+            threads
+        };
+
+        Self { threads }
+    }
+    /// ## map jobs on the pool
+    /// each job is called once, results are returned in the same order as `jobs`
+    pub fn map<T, R, F>(&self, jobs: &[T], f: F) -> Vec<R>
+    where
+        T: Sync,
+        R: Send,
+        F: Fn(&T) -> R + Sync,
+    {
+        let workers = self.threads.min(jobs.len());
+        if workers <= 1 {
+            return jobs.iter().map(f).collect();
+        }
+
+        let next = AtomicUsize::new(0);
+        let results = Mutex::new((0..jobs.len()).map(|_| None).collect::<Vec<Option<R>>>());
+
+        thread::scope(|scope| {
+            for _ in 0..workers {
+                scope.spawn(|| loop {
+                    let index = next.fetch_add(1, Ordering::Relaxed);
+                    if index >= jobs.len() {
+                        break;
+                    }
+                    let res = f(&jobs[index]);
+                    results.lock().unwrap()[index] = Some(res);
+                });
+            }
+        });
+
+        results
+            .into_inner()
+            .unwrap()
+            .into_iter()
+            .map(|res| res.expect("worker pool job not finished"))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod test_pool {
+    use super::WorkerPool;
+
+    #[test]
+    fn ordered() {
+        let jobs = (0..100).collect::<Vec<usize>>();
+        let res = WorkerPool::new(4).map(&jobs, |job| job * 2);
+
+        assert_eq!(res, jobs.iter().map(|job| job * 2).collect::<Vec<usize>>());
+    }
+}
@@ -0,0 +1,406 @@
+use std::{
+    collections::{BTreeMap, HashMap},
+    path::{Path, PathBuf},
+};
+
+use cargo_metadata::diagnostic::{Diagnostic as CargoDiagnostic, DiagnosticLevel};
+use gen_utils::{
+    common::{
+        fs::{self, GenUIFs},
+        Source,
+    },
+    error::Error,
+};
+use serde_json::{json, Value};
+
+use super::{Diagnostic, Severity};
+
+/// file name of the source map in the compiled project
+pub const SOURCE_MAP: &str = ".gen_ui_source_map.json";
+
+/// # Source Map
+/// map lines of the generated `.rs` files back to the `.gen` files, paths are relative to the workspace
+///
+/// the underlayer does not report spans, so lines are aligned by content: a generated line is mapped to the
+/// same code in the `.gen` file (such as code in `<script>`) if the code is only in one line of it, other lines
+/// are mapped to the nearest mapped line before them. copied `.rs` files are mapped to themselves.
+///
+/// the compiler writes it to `<compiled>/.gen_ui_source_map.json` after each compile pass:
+/// ```json
+/// {
+///   "version": 1,
+///   "files": {
+///     "src_gen_0/src/views/home.rs": {"source": "hello/views/home.gen", "lines": [null, [12, 4]]},
+///     "src_gen_0/src/utils.rs": {"source": "hello/utils.rs", "lines": "copy"}
+///   }
+/// }
+/// ```
+/// each item of `lines` is `[line, column offset]` of a generated line (start from 1) or `null`
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SourceMap {
+    files: BTreeMap<PathBuf, FileMap>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct FileMap {
+    source: PathBuf,
+    /// `None` means the file is copied from the source
+    lines: Option<Vec<Option<(usize, isize)>>>,
+}
+
+/// # Source Location
+/// a location in the source project which is mapped from the generated code
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SourceLocation {
+    /// relative to the workspace
+    pub file: PathBuf,
+    pub line: Option<usize>,
+    pub column: Option<usize>,
+}
+
+impl SourceMap {
+    /// ## build the source map of the source files (absolute paths, see `Cache::sources`)
+    /// files which are not compiled (such as failed ones) are skipped
+    pub fn build<I>(source: &Source, files: I) -> Self
+    where
+        I: IntoIterator<Item = PathBuf>,
+    {
+        let mut map = Self::default();
+        for file in files {
+            let Ok(compiled) = file.to_compiled_from_source(source) else {
+                continue;
+            };
+            let (Ok(generated), Ok(from)) = (
+                compiled.strip_prefix(source.path.as_path()),
+                file.strip_prefix(source.path.as_path()),
+            ) else {
+                continue;
+            };
+
+            if file.is_gen_file() {
+                if let (Ok(gen), Ok(rs)) = (
+                    std::fs::read_to_string(file.as_path()),
+                    std::fs::read_to_string(compiled.as_path()),
+                ) {
+                    map.insert_gen(generated, from, &gen, &rs);
+                }
+            } else if file.extension().is_some_and(|ext| ext == "rs") && compiled.exists() {
+                map.insert_copy(generated, from);
+            }
+        }
+        map
+    }
+    /// ## map the generated file to the `.gen` file by aligning the lines
+    pub fn insert_gen<P>(&mut self, generated: P, source: P, gen: &str, rs: &str)
+    where
+        P: AsRef<Path>,
+    {
+        // trimmed code -> lines in the `.gen` file
+        let mut index: HashMap<&str, Vec<(usize, usize)>> = HashMap::new();
+        for (line, code) in gen.lines().enumerate() {
+            if is_significant(code.trim()) {
+                index
+                    .entry(code.trim())
+                    .or_default()
+                    .push((line + 1, indent(code)));
+            }
+        }
+
+        let lines = rs
+            .lines()
+            .map(|code| match index.get(code.trim())?.as_slice() {
+                [(line, gen_indent)] => Some((*line, *gen_indent as isize - indent(code) as isize)),
+                // the same code in several lines is ambiguous, a wrong line is worse than none
+                _ => None,
+            })
+            .collect();
+
+        self.files.insert(
+            generated.as_ref().to_path_buf(),
+            FileMap {
+                source: source.as_ref().to_path_buf(),
+                lines: Some(lines),
+            },
+        );
+    }
+    /// ## map the copied file to itself
+    pub fn insert_copy<P>(&mut self, generated: P, source: P)
+    where
+        P: AsRef<Path>,
+    {
+        self.files.insert(
+            generated.as_ref().to_path_buf(),
+            FileMap {
+                source: source.as_ref().to_path_buf(),
+                lines: None,
+            },
+        );
+    }
+    pub fn is_empty(&self) -> bool {
+        self.files.is_empty()
+    }
+    /// ## find the source location of the generated code
+    /// - file: relative to the workspace (as cargo reports)
+    /// - line, column: start from 1
+    pub fn lookup<P>(&self, file: P, line: usize, column: usize) -> Option<SourceLocation>
+    where
+        P: AsRef<Path>,
+    {
+        let map = self.files.get(file.as_ref())?;
+        let Some(lines) = map.lines.as_ref() else {
+            return Some(SourceLocation {
+                file: map.source.to_path_buf(),
+                line: Some(line),
+                column: Some(column),
+            });
+        };
+
+        let index = line.checked_sub(1)?.min(lines.len().checked_sub(1)?);
+        let (line, column) = match lines[index] {
+            Some((line, offset)) => (Some(line), Some((column as isize + offset).max(1) as usize)),
+            // nearest mapped line before, the file itself if there is none
+            None => (
+                lines[..index]
+                    .iter()
+                    .rev()
+                    .find_map(|mapped| mapped.map(|(line, _)| line)),
+                None,
+            ),
+        };
+        Some(SourceLocation {
+            file: map.source.to_path_buf(),
+            line,
+            column,
+        })
+    }
+    /// ## convert the diagnostic of cargo to the diagnostic of the source project
+    /// - root: the workspace, where cargo runs
+    ///
+    /// return `None` if the primary span is not in a mapped file, the location of the generated code
+    /// is kept as a note
+    pub fn map_cargo(&self, root: &Path, diagnostic: &CargoDiagnostic) -> Option<Diagnostic> {
+        let severity = match diagnostic.level {
+            DiagnosticLevel::Error | DiagnosticLevel::Ice => Severity::Error,
+            DiagnosticLevel::Warning => Severity::Warning,
+            _ => return None,
+        };
+        let span = diagnostic.spans.iter().find(|span| span.is_primary)?;
+        let file = Path::new(&span.file_name);
+        let location = self.lookup(
+            file.strip_prefix(root).unwrap_or(file),
+            span.line_start,
+            span.column_start,
+        )?;
+
+        let mut mapped = Diagnostic::new(
+            root.join(location.file),
+            severity,
+            diagnostic.message.to_string(),
+        )
+        .with_note(&format!(
+            "generated code: {}:{}:{}",
+            span.file_name, span.line_start, span.column_start
+        ));
+        mapped.line = location.line;
+        mapped.column = location.column;
+        for child in diagnostic.children.iter() {
+            match child.level {
+                DiagnosticLevel::Help if mapped.hint.is_none() => {
+                    mapped.hint.replace(child.message.to_string());
+                }
+                _ => mapped.notes.push(child.message.to_string()),
+            }
+        }
+        Some(mapped)
+    }
+    /// ## write the source map into the compiled project
+    pub fn write<P>(&self, compiled: P) -> Result<(), Error>
+    where
+        P: AsRef<Path>,
+    {
+        fs::write(
+            compiled.as_ref().join(SOURCE_MAP),
+            &self.to_value().to_string(),
+        )
+    }
+    /// ## read the source map from the compiled project, empty if it does not exist
+    pub fn read<P>(compiled: P) -> Result<Self, Error>
+    where
+        P: AsRef<Path>,
+    {
+        let path = compiled.as_ref().join(SOURCE_MAP);
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+        let value = serde_json::from_str::<Value>(&fs::read(path.as_path())?)
+            .map_err(|e| Error::from(format!("{}: {}", fs::path_to_str(&path), e)))?;
+        Self::try_from(&value)
+    }
+    pub fn to_value(&self) -> Value {
+        let files = self
+            .files
+            .iter()
+            .map(|(generated, map)| {
+                let lines = map.lines.as_ref().map_or_else(
+                    || json!("copy"),
+                    |lines| {
+                        lines
+                            .iter()
+                            .map(|line| {
+                                line.map_or(Value::Null, |(line, offset)| json!([line, offset]))
+                            })
+                            .collect::<Value>()
+                    },
+                );
+                (
+                    fs::path_to_str(generated),
+                    json!({"source": fs::path_to_str(&map.source), "lines": lines}),
+                )
+            })
+            .collect::<serde_json::Map<String, Value>>();
+
+        json!({"version": 1, "files": files})
+    }
+}
+
+impl TryFrom<&Value> for SourceMap {
+    type Error = Error;
+
+    fn try_from(value: &Value) -> Result<Self, Self::Error> {
+        let err =
+            || Error::from("source map format is not correct, compile the project again to fix it");
+        let mut map = Self::default();
+        for (generated, file) in value
+            .get("files")
+            .and_then(Value::as_object)
+            .ok_or_else(err)?
+        {
+            let source = file.get("source").and_then(Value::as_str).ok_or_else(err)?;
+            let lines = match file.get("lines").ok_or_else(err)? {
+                Value::Array(lines) => Some(
+                    lines
+                        .iter()
+                        .map(|line| match line {
+                            Value::Null => Ok(None),
+                            Value::Array(pair) if pair.len() == 2 => Ok(Some((
+                                pair[0].as_u64().ok_or_else(err)? as usize,
+                                pair[1].as_i64().ok_or_else(err)? as isize,
+                            ))),
+                            _ => Err(err()),
+                        })
+                        .collect::<Result<Vec<_>, Error>>()?,
+                ),
+                _ => None,
+            };
+            map.files.insert(
+                PathBuf::from(generated),
+                FileMap {
+                    source: PathBuf::from(source),
+                    lines,
+                },
+            );
+        }
+        Ok(map)
+    }
+}
+
+/// lines which only have punctuations (such as `}`) can not be aligned
+fn is_significant(code: &str) -> bool {
+    code.chars().any(char::is_alphanumeric)
+}
+
+fn indent(code: &str) -> usize {
+    code.chars().take_while(|c| c.is_whitespace()).count()
+}
+
+#[cfg(test)]
+mod test_source_map {
+    use std::path::{Path, PathBuf};
+
+    use serde_json::json;
+
+    use super::{SourceLocation, SourceMap};
+
+    #[test]
+    fn lookup() {
+        let gen = "<template>\n  <view></view>\n</template>\n\n<script>\nfn add(a: i32) -> i32 {\n    a + one\n}\nlet x = 1;\nlet x = 1;\n</script>\n";
+        let rs = "use makepad_widgets::*;\nfn add(a: i32) -> i32 {\n        a + one\n}\nlet x = 1;\n";
+        let mut map = SourceMap::default();
+        map.insert_gen("src_gen_0/src/home.rs", "hello/home.gen", gen, rs);
+        map.insert_copy("src_gen_0/src/utils.rs", "hello/utils.rs");
+
+        let at = |file: &str, line: usize, column: usize| map.lookup(file, line, column);
+        let location = |line: Option<usize>, column: Option<usize>| SourceLocation {
+            file: PathBuf::from("hello/home.gen"),
+            line,
+            column,
+        };
+        // `one` is at column 13 in the generated code and 9 in the `.gen` file
+        assert_eq!(
+            at("src_gen_0/src/home.rs", 3, 13),
+            Some(location(Some(7), Some(9)))
+        );
+        // `}` is not aligned, use the line before it
+        assert_eq!(
+            at("src_gen_0/src/home.rs", 4, 1),
+            Some(location(Some(7), None))
+        );
+        assert_eq!(
+            at("src_gen_0/src/home.rs", 1, 1),
+            Some(location(None, None))
+        );
+        // `let x = 1;` is in two lines of the `.gen` file, it is not aligned
+        assert_eq!(
+            at("src_gen_0/src/home.rs", 5, 1),
+            Some(location(Some(7), None))
+        );
+        assert_eq!(
+            at("src_gen_0/src/utils.rs", 2, 3).map(|l| (l.file, l.line)),
+            Some((PathBuf::from("hello/utils.rs"), Some(2)))
+        );
+        assert!(at("src_gen_0/src/main.rs", 1, 1).is_none());
+
+        let value = map.to_value();
+        assert_eq!(
+            value["files"]["src_gen_0/src/home.rs"],
+            json!({"source": "hello/home.gen", "lines": [null, [6, 0], [7, -4], null, null]})
+        );
+        assert_eq!(SourceMap::try_from(&value).unwrap(), map);
+        assert!(SourceMap::read(Path::new("/not/exists"))
+            .unwrap()
+            .is_empty());
+    }
+
+    #[test]
+    fn cargo() {
+        let mut map = SourceMap::default();
+        map.insert_gen(
+            "src_gen_0/src/home.rs",
+            "hello/home.gen",
+            "<script>\nlet a = b;\n</script>\n",
+            "fn f() {\n    let a = b;\n}\n",
+        );
+        let span = json!({
+            "file_name": "src_gen_0/src/home.rs", "byte_start": 0, "byte_end": 1,
+            "line_start": 2, "line_end": 2, "column_start": 13, "column_end": 14,
+            "is_primary": true, "text": [], "label": null,
+            "suggested_replacement": null, "suggestion_applicability": null, "expansion": null
+        });
+        let diagnostic = serde_json::from_value(json!({
+            "message": "cannot find value `b` in this scope", "code": null, "level": "error",
+            "spans": [span], "rendered": null,
+            "children": [{"message": "a local variable with a similar name exists", "code": null,
+                "level": "help", "spans": [], "children": [], "rendered": null}]
+        }))
+        .unwrap();
+
+        let mapped = map.map_cargo(Path::new("/ws"), &diagnostic).unwrap();
+        assert_eq!(mapped.file, PathBuf::from("/ws/hello/home.gen"));
+        assert_eq!((mapped.line, mapped.column), (Some(2), Some(9)));
+        assert_eq!(mapped.notes, vec!["generated code: src_gen_0/src/home.rs:2:13"]);
+        assert_eq!(
+            mapped.hint.as_deref(),
+            Some("a local variable with a similar name exists")
+        );
+    }
+}
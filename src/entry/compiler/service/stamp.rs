@@ -0,0 +1,62 @@
+use std::{
+    collections::HashMap,
+    fs::File,
+    path::{Path, PathBuf},
+    time::SystemTime,
+};
+
+use walkdir::WalkDir;
+
+use super::calc_hash;
+
+/// # Output Stamps
+/// record the hash and modified time of every file in the compiled project before an incremental compile
+///
+/// the underlayer compiler regenerates the files it owns (such as `lib.rs`, `app.rs`), after compile we
+/// restore the modified time of the files whose content is not changed, so cargo will not rebuild them
+#[derive(Debug, Default)]
+pub struct OutputStamps {
+    values: HashMap<PathBuf, (String, SystemTime)>,
+}
+
+impl OutputStamps {
+    /// ## record stamps of the compiled project
+    /// `target` dir (cargo build output) and `Cargo.lock` are skipped
+    pub fn new<P>(path: P) -> Self
+    where
+        P: AsRef<Path>,
+    {
+        let ignores = [
+            path.as_ref().join("target"),
+            path.as_ref().join("Cargo.lock"),
+        ];
+        let values = WalkDir::new(path.as_ref())
+            .into_iter()
+            .filter_entry(|e| !ignores.iter().any(|ignore| e.path().starts_with(ignore)))
+            .filter_map(|e| e.ok())
+            .filter(|e| e.file_type().is_file())
+            .filter_map(|e| {
+                let modified = e.metadata().ok()?.modified().ok()?;
+                let hash = calc_hash(e.path()).ok()?;
+                Some((e.path().to_path_buf(), (hash, modified)))
+            })
+            .collect();
+
+        Self { values }
+    }
+    /// ## restore the modified time of unchanged files
+    /// return the number of files which are restored
+    pub fn restore(&self) -> usize {
+        self.values
+            .iter()
+            .filter(|(path, (hash, modified))| {
+                calc_hash(path.as_path()).is_ok_and(|current| current.eq(hash))
+                    && File::options()
+                        .write(true)
+                        .open(path.as_path())
+                        .and_then(|file| file.set_modified(*modified))
+                        .is_ok()
+            })
+            .count()
+    }
+}
@@ -10,38 +10,66 @@
 //! 所以Watcher监听的是workspace下的`hello`目录
 //! 在开启监视前会获取`hello`目录下的`gen_ui.toml`文件，然后根据`gen_ui.toml`文件中的`[watcher]`配置来进行监听。
 
-use std::path::Path;
+use std::{
+    path::{Path, PathBuf},
+    sync::mpsc::{Receiver, Sender},
+};
 
-use crate::entry::compiler::excludes::Excludes;
+use crate::entry::compiler::{conf::WatcherConf, excludes::ExcludeMatcher};
 #[cfg(target_os = "macos")]
 use gen_utils::common::fs::FileState;
 use gen_utils::error::Error;
 
+/// # Watch Message
+/// messages handled by the watcher loop, besides file events others can send messages by the sender
+/// (such as the daemon, see `Daemon`)
+#[derive(Debug)]
+pub enum WatchMsg {
+    /// file event from notify
+    Event(notify::Result<notify::Event>),
+    /// compile the file as it is modified
+    Recompile(PathBuf),
+    /// stop watching
+    Shutdown,
+}
+
 /// ## init watcher
 /// init watcher to watch file change event
+/// - conf: native file events or polling, see [WatcherConf]
+/// - channel: messages of the watcher loop, file events are sent by the watcher
 /// - f: callback function, we can do something when file change
 #[cfg(not(target_os = "macos"))]
 pub fn init_watcher<P, F>(
     path: P,
-    excludes: &Excludes,
+    excludes: &ExcludeMatcher,
+    conf: &WatcherConf,
+    (tx, rx): (Sender<WatchMsg>, Receiver<WatchMsg>),
     mut f: F,
 ) -> Result<(), Box<dyn std::error::Error>>
 where
     P: AsRef<Path>,
     F: FnMut(&Path, &notify::EventKind) -> Result<(), Error>,
 {
-    use std::{sync::mpsc::channel, time::Duration};
+    use std::time::Duration;
 
-    use notify::{Config, RecommendedWatcher, RecursiveMode, Watcher};
+    use notify::{
+        event::{DataChange, ModifyKind, RemoveKind},
+        Config, EventKind, PollWatcher, RecommendedWatcher, RecursiveMode, Watcher,
+    };
 
     use crate::log::compiler::{CompilerLogger, CompilerLogs};
 
-    let (tx, rx) = channel();
     // [config for watcher] --------------------------------------------------------------------------------
-    let config = Config::default();
-    config.with_poll_interval(Duration::from_secs(10));
+    let config = Config::default().with_poll_interval(Duration::from_millis(conf.poll_interval));
     // [watcher] -------------------------------------------------------------------------------------------
-    let mut watcher = RecommendedWatcher::new(tx, config)?;
+    let handler = move |event| {
+        let _ = tx.send(WatchMsg::Event(event));
+    };
+    let mut watcher: Box<dyn Watcher> = if conf.poll {
+        Box::new(PollWatcher::new(handler, config)?)
+    } else {
+        Box::new(RecommendedWatcher::new(handler, config)?)
+    };
     // let mut fs_state = get_current_state(path)?;
 
     watcher.watch(path.as_ref(), RecursiveMode::Recursive)?;
@@ -49,13 +77,29 @@ where
         .compiler()
         .info();
 
-    while let Ok(event) = rx.recv() {
+    while let Ok(msg) = rx.recv() {
+        let event = match msg {
+            WatchMsg::Event(event) => event,
+            WatchMsg::Recompile(path) => {
+                if let Err(e) = f(
+                    path.as_path(),
+                    &EventKind::Modify(ModifyKind::Data(DataChange::Any)),
+                ) {
+                    CompilerLogger::new(&e.to_string()).error();
+                }
+                continue;
+            }
+            WatchMsg::Shutdown => break,
+        };
         match event {
             Ok(event) => {
-                // filter exclude
-                if !excludes.contains(path.as_ref(), &event.paths[0]) {
-                    if let Err(e) = f(&event.paths[0], &event.kind) {
-                        CompilerLogger::new(&e.to_string()).error();
+                // filter exclude, rename event (`RenameMode::Both`) has both old path and new path
+                let removed_dir = matches!(event.kind, EventKind::Remove(RemoveKind::Folder));
+                for event_path in event.paths.iter() {
+                    if !excludes.is_excluded_as(event_path, removed_dir || event_path.is_dir()) {
+                        if let Err(e) = f(event_path, &event.kind) {
+                            CompilerLogger::new(&e.to_string()).error();
+                        }
                     }
                 }
             }
@@ -84,28 +128,35 @@ where
 #[cfg(target_os = "macos")]
 pub fn init_watcher<P, F>(
     path: P,
-    excludes: &Excludes,
+    excludes: &ExcludeMatcher,
+    conf: &WatcherConf,
+    (tx, rx): (Sender<WatchMsg>, Receiver<WatchMsg>),
     mut f: F,
 ) -> Result<(), Box<dyn std::error::Error>>
 where
     P: AsRef<Path>,
     F: FnMut(&Path, FileState) -> Result<(), Error>,
 {
-    use std::{sync::mpsc::channel, time::Duration};
+    use std::time::Duration;
 
-    use notify::{Config, RecommendedWatcher, RecursiveMode, Watcher};
+    use notify::{Config, PollWatcher, RecommendedWatcher, RecursiveMode, Watcher};
 
     use crate::{
         entry::compiler::service::FileEventTracker,
         log::compiler::{CompilerLogger, CompilerLogs},
     };
 
-    let (tx, rx) = channel();
     // [config for watcher] --------------------------------------------------------------------------------
-    let config = Config::default();
-    config.with_poll_interval(Duration::from_secs(10));
+    let config = Config::default().with_poll_interval(Duration::from_millis(conf.poll_interval));
     // [watcher] -------------------------------------------------------------------------------------------
-    let mut watcher = RecommendedWatcher::new(tx, config)?;
+    let handler = move |event| {
+        let _ = tx.send(WatchMsg::Event(event));
+    };
+    let mut watcher: Box<dyn Watcher> = if conf.poll {
+        Box::new(PollWatcher::new(handler, config)?)
+    } else {
+        Box::new(RecommendedWatcher::new(handler, config)?)
+    };
 
     watcher.watch(path.as_ref(), RecursiveMode::Recursive)?;
     CompilerLogs::WatcherInit(path.as_ref().to_path_buf())
@@ -116,17 +167,31 @@ where
     let mut tracker = FileEventTracker::new();
 
     // 这里的事件处理需要进行防抖处理
-    while let Ok(event) = rx.recv() {
+    while let Ok(msg) = rx.recv() {
+        let event = match msg {
+            WatchMsg::Event(event) => event,
+            // recompile is not a file event, no need to debounce
+            WatchMsg::Recompile(path) => {
+                if let Err(e) = f(path.as_path(), FileState::Modified) {
+                    CompilerLogger::new(&e.to_string()).error();
+                }
+                continue;
+            }
+            WatchMsg::Shutdown => break,
+        };
         match event {
             Ok(event) => {
-                let compiled_path = event.paths[0].to_path_buf();
-                if !excludes.contains(path.as_ref(), compiled_path.as_path()) {
-                    tracker.set_path(compiled_path.as_path());
+                // rename event may have both old path and new path, the tracker follows them one by one
+                for event_path in event.paths.iter() {
+                    if excludes.is_excluded(event_path) {
+                        continue;
+                    }
+                    tracker.set_path(event_path);
                     tracker.insert(event.kind);
                     if let Some(state) = tracker.state() {
-                        if let Err(e) = f(compiled_path.as_path(), state) {
+                        if let Err(e) = f(event_path, state) {
                             CompilerLogger::new(&e.to_string()).error();
-                        }else{
+                        } else {
                             tracker.flesh();
                         }
                     }
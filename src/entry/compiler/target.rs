@@ -5,17 +5,16 @@ use std::{
 };
 use clap::ValueEnum;
 use gen_utils::{
-    common::{DepType, RustDependence, Source, ToToml},
-    compiler::{CompilerImpl, UnderlayerConfImpl},
+    common::{DepType, RustDependence, ToToml},
+    compiler::UnderlayerConfImpl,
     error::Error,
 };
 use makepad_gen_plugin::compiler::{
-    Compiler as MakepadCompiler, Config as MakepadConfig,
-    CONF_FORMAT_SUGGESTION as MAKEPAD_CONF_FORMAT_SUGGESTION,
+    Config as MakepadConfig, CONF_FORMAT_SUGGESTION as MAKEPAD_CONF_FORMAT_SUGGESTION,
 };
 use toml_edit::{DocumentMut, Formatted, InlineTable, Item, Value};
 use crate::entry::ChainEnvToml;
-use super::GenUIConf;
+use super::{registry::CustomConf, GenUIConf, OutputConf};
 
 #[derive(Debug, Clone, Copy, Default, PartialEq, Eq, ValueEnum)]
 pub enum Underlayer {
@@ -35,16 +34,11 @@ impl Underlayer {
         let toml = GenUIConf::try_from((path.as_ref().to_path_buf(), *self))?;
         toml.write(path.as_ref().join("gen_ui.toml"))
     }
-    pub fn compiler(
-        &self,
-        source: &Source,
-        conf: &Box<dyn UnderlayerConfImpl>,
-    ) -> Result<Box<dyn CompilerImpl>, Error> {
-        let compiler = match self {
-            Underlayer::Makepad => MakepadCompiler::new(source.clone(), conf),
-        }?;
-
-        Ok(Box::new(compiler))
+    /// name of the underlayer in `[compiler] target`
+    pub fn name(&self) -> &'static str {
+        match self {
+            Underlayer::Makepad => "makepad",
+        }
     }
 }
 
@@ -67,19 +61,14 @@ impl Display for Underlayer {
 
 impl From<&Underlayer> for Value {
     fn from(value: &Underlayer) -> Self {
-        Value::String(Formatted::new(
-            match value {
-                Underlayer::Makepad => "makepad",
-            }
-            .to_string(),
-        ))
+        Value::String(Formatted::new(value.name().to_string()))
     }
 }
 
-#[allow(dead_code)]
 #[derive(Debug)]
 pub struct CompileUnderlayer {
     pub target: Box<dyn UnderlayerConfImpl>,
+    /// configs of the extra outputs, in the same order as `[[compiler.outputs]]` (see [OutputConf])
     pub others: Option<Vec<Box<dyn UnderlayerConfImpl>>>,
 }
 
@@ -93,12 +82,44 @@ impl TryFrom<(PathBuf, Underlayer)> for CompileUnderlayer {
     }
 }
 
-impl TryFrom<(DocumentMut, Underlayer)> for CompileUnderlayer {
+/// read the config of the target (`[compiler] target`) from gen_ui.toml,
+/// targets which are not builtin use the `[<name>]` table as [CustomConf]
+impl TryFrom<(DocumentMut, &str)> for CompileUnderlayer {
     type Error = Error;
 
-    fn try_from(value: (DocumentMut, Underlayer)) -> Result<Self, Self::Error> {
-        let (mut toml, target) = value;
-        let target = match target {
+    fn try_from(value: (DocumentMut, &str)) -> Result<Self, Self::Error> {
+        let (mut toml, name) = value;
+
+        Ok(Self {
+            target: Self::conf(&mut toml, name)?,
+            others: None,
+        })
+    }
+}
+
+impl CompileUnderlayer {
+    /// ## read the configs of the extra outputs (`[[compiler.outputs]]`)
+    pub fn with_others(
+        mut self,
+        mut toml: DocumentMut,
+        outputs: &[OutputConf],
+    ) -> Result<Self, Error> {
+        if !outputs.is_empty() {
+            self.others = Some(
+                outputs
+                    .iter()
+                    .map(|output| Self::conf(&mut toml, output.target.as_str()))
+                    .collect::<Result<Vec<Box<dyn UnderlayerConfImpl>>, Error>>()?,
+            );
+        }
+        Ok(self)
+    }
+    /// config of the underlayer which is the `[<name>]` table in gen_ui.toml
+    fn conf(toml: &mut DocumentMut, name: &str) -> Result<Box<dyn UnderlayerConfImpl>, Error> {
+        let Ok(target) = name.parse::<Underlayer>() else {
+            return Ok(Box::new(CustomConf::new(name, toml.get(name))?));
+        };
+        match target {
             Underlayer::Makepad => toml.get_mut("makepad").map_or_else(
                 || Err(Error::from(MAKEPAD_CONF_FORMAT_SUGGESTION)),
                 |table| {
@@ -129,19 +150,12 @@ impl TryFrom<(DocumentMut, Underlayer)> for CompileUnderlayer {
                             );
                         });
 
-                    MakepadConfig::try_from(table).and_then(|conf| Ok(Box::new(conf)))
+                    MakepadConfig::try_from(table)
+                        .map(|conf| Box::new(conf) as Box<dyn UnderlayerConfImpl>)
                 },
             ),
-        }?;
-
-        Ok(Self {
-            target,
-            others: None,
-        })
+        }
     }
-}
-
-impl CompileUnderlayer {
     /// default makepad toml
     fn makepad(root: PathBuf) -> Result<Self, Error> {
         let chain_env_toml: ChainEnvToml = ChainEnvToml::path()?.try_into()?;
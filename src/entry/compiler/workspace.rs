@@ -0,0 +1,176 @@
+use std::fmt::Display;
+
+use gen_utils::error::Error;
+use toml_edit::{value, Array, DocumentMut, Item, Table, TableLike};
+
+/// # Workspace Repair
+/// a step to make the super project's `Cargo.toml` a workspace which contains the compiled project
+///
+/// repairs are applied by `toml_edit`, so existing comments and formatting are kept
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Repair {
+    /// `Cargo.toml` does not exist
+    Create,
+    /// `[workspace]` does not exist
+    Workspace,
+    /// `workspace.members` does not exist
+    Members,
+    /// the compiled project is not in `workspace.members`
+    Member(String),
+    /// `workspace.resolver` does not exist
+    Resolver,
+}
+
+impl Repair {
+    /// create the manifest or turn it into a workspace need to be confirmed by the user,
+    /// others are always safe to do
+    pub fn need_confirm(&self) -> bool {
+        matches!(self, Repair::Create | Repair::Workspace)
+    }
+}
+
+impl Display for Repair {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Repair::Create => f.write_str("create Cargo.toml"),
+            Repair::Workspace => f.write_str("add [workspace]"),
+            Repair::Members => f.write_str("add workspace.members"),
+            Repair::Member(member) => write!(f, "add `{}` to workspace.members", member),
+            Repair::Resolver => f.write_str("add workspace.resolver = \"2\""),
+        }
+    }
+}
+
+/// ## check the workspace manifest
+/// - doc: the super project's `Cargo.toml`, `None` if it does not exist
+/// - member: the compiled project which should be a member of the workspace
+///
+/// return the repairs in order, error if the manifest can not be repaired without losing user's config
+/// (such as `workspace` or `workspace.members` has a wrong type)
+pub fn check(doc: Option<&DocumentMut>, member: &str) -> Result<Vec<Repair>, Error> {
+    let all = |mut repairs: Vec<Repair>| {
+        repairs.extend([
+            Repair::Members,
+            Repair::Member(member.to_string()),
+            Repair::Resolver,
+        ]);
+        repairs
+    };
+
+    let Some(doc) = doc else {
+        return Ok(all(vec![Repair::Create, Repair::Workspace]));
+    };
+    let Some(workspace) = doc.get("workspace") else {
+        return Ok(all(vec![Repair::Workspace]));
+    };
+    let workspace = workspace
+        .as_table_like()
+        .ok_or_else(|| Error::from("`workspace` in Cargo.toml must be a table"))?;
+
+    let mut repairs = vec![];
+    match workspace.get("members") {
+        Some(members) => {
+            let members = members
+                .as_array()
+                .ok_or_else(|| Error::from("`workspace.members` in Cargo.toml must be an array"))?;
+            if !members.iter().any(|item| item.as_str() == Some(member)) {
+                repairs.push(Repair::Member(member.to_string()));
+            }
+        }
+        None => repairs.extend([Repair::Members, Repair::Member(member.to_string())]),
+    }
+    if !workspace.contains_key("resolver") {
+        repairs.push(Repair::Resolver);
+    }
+    Ok(repairs)
+}
+
+/// ## apply the repairs (from [check]) to the manifest
+pub fn repair(doc: &mut DocumentMut, repairs: &[Repair]) {
+    for repair in repairs {
+        match repair {
+            Repair::Create => {}
+            Repair::Workspace => {
+                doc.insert("workspace", Item::Table(Table::new()));
+            }
+            Repair::Members => {
+                if let Some(workspace) = workspace_mut(doc) {
+                    workspace.insert("members", value(Array::new()));
+                }
+            }
+            Repair::Member(member) => {
+                if let Some(members) = workspace_mut(doc)
+                    .and_then(|workspace| workspace.get_mut("members"))
+                    .and_then(Item::as_array_mut)
+                {
+                    members.push(member.as_str());
+                }
+            }
+            Repair::Resolver => {
+                if let Some(workspace) = workspace_mut(doc) {
+                    workspace.insert("resolver", value("2"));
+                }
+            }
+        }
+    }
+}
+
+fn workspace_mut(doc: &mut DocumentMut) -> Option<&mut dyn TableLike> {
+    doc.get_mut("workspace").and_then(Item::as_table_like_mut)
+}
+
+#[cfg(test)]
+mod test_workspace {
+    use toml_edit::DocumentMut;
+
+    use super::{check, repair, Repair};
+
+    #[test]
+    fn repair_package() {
+        let mut doc = r#"# my project
+[package]
+name = "hello" # the name
+version = "0.1.0"
+"#
+        .parse::<DocumentMut>()
+        .unwrap();
+
+        let repairs = check(Some(&doc), "src_gen_0").unwrap();
+        assert!(repairs.iter().any(Repair::need_confirm));
+        repair(&mut doc, &repairs);
+        assert_eq!(
+            doc.to_string(),
+            r#"# my project
+[package]
+name = "hello" # the name
+version = "0.1.0"
+
+[workspace]
+members = ["src_gen_0"]
+resolver = "2"
+"#
+        );
+        assert!(check(Some(&doc), "src_gen_0").unwrap().is_empty());
+    }
+
+    #[test]
+    fn repair_members() {
+        let mut doc = "[workspace]\n# members of the workspace\nmembers = [\n    \"hello\",\n]\n"
+            .parse::<DocumentMut>()
+            .unwrap();
+
+        let repairs = check(Some(&doc), "src_gen_0").unwrap();
+        assert_eq!(
+            repairs,
+            vec![Repair::Member("src_gen_0".to_string()), Repair::Resolver]
+        );
+        repair(&mut doc, &repairs);
+        assert_eq!(
+            doc.to_string(),
+            "[workspace]\n# members of the workspace\nmembers = [\n    \"hello\", \"src_gen_0\",\n]\nresolver = \"2\"\n"
+        );
+
+        let doc = "workspace = 1".parse::<DocumentMut>().unwrap();
+        assert!(check(Some(&doc), "src_gen_0").is_err());
+    }
+}
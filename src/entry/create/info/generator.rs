@@ -4,7 +4,7 @@ pub mod makepad;
 use super::ProjectInfoType;
 use crate::{
     common::DEFAULT_GITIGNORE,
-    entry::{FrameworkType, RactToml},
+    entry::{FrameworkType, RactToml, Template},
     log::{CreateLogs, LogExt},
 };
 use gen_utils::{common::fs, error::Error};
@@ -15,6 +15,8 @@ pub struct Generator {
     pub info: ProjectInfoType,
     pub git: bool,
     pub framework: FrameworkType,
+    /// files and dirs of the new project, default is [Template::builtin]
+    pub template: Template,
 }
 
 impl Generator {
@@ -27,6 +29,7 @@ impl Generator {
             info,
             git: false,
             framework,
+            template: Template::builtin(),
         }
     }
     /// ## Generate target project
@@ -55,7 +58,12 @@ impl Generator {
     /// gen_ui project is a workspace project
     fn gen_ui(&self, ract_toml: RactToml) -> Result<PathBuf, Error> {
         if let ProjectInfoType::Workspace(workspace_info) = &self.info {
-            gen_ui::create(self.path.as_path(), workspace_info, &ract_toml)
+            gen_ui::create(
+                self.path.as_path(),
+                workspace_info,
+                &ract_toml,
+                &self.template,
+            )
         } else {
             Err(Error::from("gen_ui project must be a workspace project"))
         }
@@ -65,10 +73,15 @@ impl Generator {
     fn makepad(&self, ract_toml: RactToml) -> Result<PathBuf, Error> {
         match &self.info {
             ProjectInfoType::Workspace(workspace_info) => {
-                makepad::create_workspace(self.path.as_path(), workspace_info, &ract_toml)
+                makepad::create_workspace(
+                    self.path.as_path(),
+                    workspace_info,
+                    &ract_toml,
+                    &self.template,
+                )
             }
             ProjectInfoType::Project(project_info) => {
-                makepad::create_project(self.path.as_path(), project_info)
+                makepad::create_project(self.path.as_path(), project_info, None, &self.template)
             }
         }
     }
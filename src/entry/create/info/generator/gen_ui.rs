@@ -6,15 +6,17 @@ use gen_utils::{
 };
 
 use crate::{
-    common::{
-        create_workspace, COMPONENT_MOD_GEN, EASY_GEN, GENUI_README, HELLO_GEN, HOME_GEN, ROOT_GEN,
-        VIEW_MOD_GEN,
-    },
-    entry::{ProjectInfo, RactToml, WorkspaceInfo},
+    common::create_workspace,
+    entry::{FrameworkType, ProjectInfo, RactToml, Scope, Template, Vars, WorkspaceInfo},
     log::{CreateLogs, LogExt, LogItem},
 };
 
-pub fn create<P>(path: P, info: &WorkspaceInfo, ract_toml: &RactToml) -> Result<PathBuf, Error>
+pub fn create<P>(
+    path: P,
+    info: &WorkspaceInfo,
+    ract_toml: &RactToml,
+    template: &Template,
+) -> Result<PathBuf, Error>
 where
     P: AsRef<Path>,
 {
@@ -30,18 +32,28 @@ where
     let cargo_toml = info.workspace_members_toml().to_string();
     // [create a new wrokspace] ----------------------------------------------------
     let _ = create_workspace(path.as_path(), &cargo_toml, ract_toml)?;
-    // [create README.md] ----------------------------------------------------------
-    let _ = fs::write(path.as_path().join("README.md"), GENUI_README)?;
+    // [workspace files of the template, e.g. README.md] ---------------------------
+    template.apply(
+        path.as_path(),
+        Scope::Workspace,
+        FrameworkType::GenUI,
+        &Vars::workspace(info, FrameworkType::GenUI),
+    )?;
     // [create projects] -----------------------------------------------------------
     for project in &info.members {
-        let _ = create_project(path.as_path(), project)?;
+        let _ = create_project(path.as_path(), project, &info.name, template)?;
     }
 
     Ok(path)
 }
 
 // create GenUI project depend on project info
-fn create_project<P>(path: P, info: &ProjectInfo) -> Result<(), Error>
+pub fn create_project<P>(
+    path: P,
+    info: &ProjectInfo,
+    workspace: &str,
+    template: &Template,
+) -> Result<(), Error>
 where
     P: AsRef<Path>,
 {
@@ -58,23 +70,13 @@ where
                 if out.status.success() {
                     CreateLogs::Cargo.success(crate::entry::Language::En).print();
                     let ui_dir_path = path.as_ref().join(&info.name);
-                    // [clear main.rs] ---------------------------------------------------------------------
-                    let _ = fs::write(ui_dir_path.join("src").join("main.rs"), "")?;
-                    // [create dir: resources, views, components] ------------------------------------------
-                    for path in ["resources", "views", "components"].iter() {
-                        let _ = fs::create_dir(ui_dir_path.join(path))?;
-                    }
-                    // [create basic gen files: views/mod.gen, views/root.gen] -----------------------------
-                    for (dir, file, content) in vec![
-                        ("views", "mod.gen", VIEW_MOD_GEN),
-                        ("views", "root.gen", ROOT_GEN),
-                        ("views", "home.gen", HOME_GEN),
-                        ("components", "mod.gen", COMPONENT_MOD_GEN),
-                        ("components", "easy.gen", EASY_GEN),
-                        ("components", "hello.gen", HELLO_GEN),
-                    ] {
-                        let _ = fs::write(ui_dir_path.join(dir).join(file), content)?;
-                    }
+                    // [template: main.rs, resources, views, components, basic gen files] ------------------
+                    template.apply(
+                        ui_dir_path.as_path(),
+                        Scope::Project,
+                        FrameworkType::GenUI,
+                        &Vars::project(info, Some(workspace), FrameworkType::GenUI),
+                    )?;
                     // [handle Cargo.toml] -----------------------------------------------------------------
                     let _ = info.write(ui_dir_path.join("Cargo.toml"))?;
                     // [create config files: gen_ui.toml, .gen_ui_cache] -----------------------------------
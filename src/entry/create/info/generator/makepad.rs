@@ -9,8 +9,8 @@ use gen_utils::{
 };
 
 use crate::{
-    common::{self, MAKEPAD_APP_RS, MAKEPAD_LIB_RS, MAKEPAD_MAIN_RS},
-    entry::{ProjectInfo, RactToml, WorkspaceInfo},
+    common,
+    entry::{FrameworkType, ProjectInfo, RactToml, Scope, Template, Vars, WorkspaceInfo},
     log::{CreateLogs, LogExt, LogItem},
 };
 
@@ -18,6 +18,7 @@ pub fn create_workspace<P>(
     path: P,
     info: &WorkspaceInfo,
     ract_toml: &RactToml,
+    template: &Template,
 ) -> Result<PathBuf, Error>
 where
     P: AsRef<Path>,
@@ -33,16 +34,29 @@ where
     let cargo_toml = info.workspace_members_toml().to_string();
     // [create a new wrokspace] ----------------------------------------------------
     let _ = common::create_workspace(path.as_path(), &cargo_toml, ract_toml)?;
+    // [workspace files of the template] -------------------------------------------
+    template.apply(
+        path.as_path(),
+        Scope::Workspace,
+        FrameworkType::Makepad,
+        &Vars::workspace(info, FrameworkType::Makepad),
+    )?;
     // [create real projects] ------------------------------------------------------
     for member in info.members.iter() {
-        let _ = create_project(path.as_path(), &member);
+        let _ = create_project(path.as_path(), &member, Some(&info.name), template);
     }
 
     Ok(path)
 }
 
-/// create a default makepad project
-pub fn create_project<P>(path: P, info: &ProjectInfo) -> Result<PathBuf, Error>
+/// create a makepad project from the template
+/// - workspace: name of the workspace, `None` for a single project, which also gets the workspace files
+pub fn create_project<P>(
+    path: P,
+    info: &ProjectInfo,
+    workspace: Option<&str>,
+    template: &Template,
+) -> Result<PathBuf, Error>
 where
     P: AsRef<Path>,
 {
@@ -69,12 +83,17 @@ where
                     info.write(path.join("Cargo.toml"))?;
                     // [write a signature file] -------------------------------------------------------------------
                     RactToml::makepad().write(path.join(".ract"))?;
-                    // [create default files: lib.rs, app.rs] -----------------------------------------------------
-                    create_lib_rs(path.as_path())?;
-                    create_main_rs(path.as_path(), &info.name)?;
-                    create_app_rs(path.as_path())?;
-                    // [create a resources folder] ----------------------------------------------------------------
-                    let _ = fs::create_dir(path.join("resources"))?;
+                    // [template: lib.rs, main.rs, app.rs, resources] ---------------------------------------------
+                    let vars = Vars::project(info, workspace, FrameworkType::Makepad);
+                    template.apply(path.as_path(), Scope::Project, FrameworkType::Makepad, &vars)?;
+                    if workspace.is_none() {
+                        template.apply(
+                            path.as_path(),
+                            Scope::Workspace,
+                            FrameworkType::Makepad,
+                            &vars,
+                        )?;
+                    }
                     // [LICENSE] ----------------------------------------------------------------------------------
                     let _ = info.write_license(path.as_path());
                     // finish
@@ -85,27 +104,3 @@ where
             },
         )
 }
-
-fn create_lib_rs<P>(path: P) -> Result<(), Error>
-where
-    P: AsRef<Path>,
-{
-    fs::write(path.as_ref().join("src").join("lib.rs"), MAKEPAD_LIB_RS)
-}
-
-fn create_main_rs<P>(path: P, name: &str) -> Result<(), Error>
-where
-    P: AsRef<Path>,
-{
-    fs::write(
-        path.as_ref().join("src").join("main.rs"),
-        &MAKEPAD_MAIN_RS.replace("${project_name}", name),
-    )
-}
-
-fn create_app_rs<P>(path: P) -> Result<(), Error>
-where
-    P: AsRef<Path>,
-{
-    fs::write(path.as_ref().join("src").join("app.rs"), MAKEPAD_APP_RS)
-}
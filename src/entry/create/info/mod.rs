@@ -1,5 +1,6 @@
 mod generator;
 mod project;
+mod validate;
 mod workspace;
 
 use gen_utils::error::Error;
@@ -26,7 +27,9 @@ impl ProjectInfoType {
     {
         ProjectGenerator::new(path, self.clone(), framework)
     }
-    pub fn new(framework: FrameworkType) -> Result<Self, Error> {
+    /// ## ask the project info
+    /// - path: dir where the workspace or project is created
+    pub fn new(framework: FrameworkType, path: &Path) -> Result<Self, Error> {
         // only makepad need to select project type
         let project_type = if let FrameworkType::Makepad = framework {
             // [select project type (workspace or project)] -------------------------------
@@ -39,12 +42,12 @@ impl ProjectInfoType {
         };
 
         // [project info] -----------------------------------------------------------
-        Self::project_info(project_type, framework.is_gen_ui())
+        Self::project_info(project_type, framework.is_gen_ui(), path)
     }
-    pub fn project_info(ty: &str, is_gen_ui: bool) -> Result<Self, Error> {
+    pub fn project_info(ty: &str, is_gen_ui: bool, path: &Path) -> Result<Self, Error> {
         match ty {
-            "workspace" => WorkspaceInfo::new(is_gen_ui).map(Into::into),
-            "project" => ProjectInfo::new(is_gen_ui).map(Into::into),
+            "workspace" => WorkspaceInfo::new(is_gen_ui, path).map(Into::into),
+            "project" => ProjectInfo::new(is_gen_ui, Some(path), &[]).map(Into::into),
             _ => Err(Error::from("Invalid project type")),
         }
     }
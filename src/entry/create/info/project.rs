@@ -9,6 +9,7 @@ use gen_utils::{
 use inquire::{Confirm, Select, Text};
 use toml_edit::{value, Array, DocumentMut, Item, Table};
 
+use super::validate::{self, validator};
 use crate::entry::{FrameworkType, Underlayer};
 
 /// # Project Info for GenUI project
@@ -44,7 +45,14 @@ impl Default for ProjectInfo {
 }
 
 impl ProjectInfo {
-    pub fn new(is_gen_ui: bool) -> Result<ProjectInfo, Error> {
+    /// ## ask the project info
+    /// - path: dir where the project is created, `None` if it does not exist yet (a new workspace)
+    /// - taken: names of the other members in the workspace
+    pub fn new(
+        is_gen_ui: bool,
+        path: Option<&Path>,
+        taken: &[String],
+    ) -> Result<ProjectInfo, Error> {
         let underlayer = if is_gen_ui {
             Some(Underlayer::from_str(
                 Select::new(
@@ -59,13 +67,24 @@ impl ProjectInfo {
             None
         };
 
+        let (dir, others) = (path.map(Path::to_path_buf), taken.to_vec());
         let name = Text::new("Project name:")
             .with_placeholder("Your project name use snake_case")
+            .with_validator(validator(move |name| {
+                validate::crate_name(name)?;
+                if let Some(dir) = dir.as_ref() {
+                    validate::not_exist(dir, name)?;
+                }
+                validate::not_taken(&others, name)
+            }))
             .prompt()
-            .map_err(|_| Error::from("Failed to get project name"))?;
+            .map_err(|_| Error::from("Failed to get project name"))?
+            .trim()
+            .to_string();
 
         let authors = Text::new("Authors name:")
             .with_placeholder("format: name <email> and use `,` to separate multiple authors")
+            .with_validator(validator(validate::authors))
             .prompt_skippable()
             .map_err(|_| Error::from("Failed to get authors name"))?
             .filter(|s| !s.is_empty());
@@ -82,8 +101,11 @@ impl ProjectInfo {
         let version = Text::new("Version:")
             .with_default("0.1.0")
             .with_placeholder("0.1.0")
+            .with_validator(validator(validate::version))
             .prompt()
-            .map_err(|_| Error::from("Failed to get version"))?;
+            .map_err(|_| Error::from("Failed to get version"))?
+            .trim()
+            .to_string();
 
         let keywords = Text::new("Keywords:")
             .with_help_message("You can input multiple keywords, or press Enter to skip")
@@ -102,12 +124,14 @@ impl ProjectInfo {
             .prompt()
             .map_err(|_| Error::from("Failed to confirm project information"))?
         {
-            let authors = authors.map(|authors| {
-                authors
-                    .split(',')
-                    .map(|author| author.parse().unwrap())
-                    .collect()
-            });
+            let authors = authors
+                .map(|authors| {
+                    authors
+                        .split(',')
+                        .map(|author| author.trim().parse())
+                        .collect::<Result<Vec<Author>, Error>>()
+                })
+                .transpose()?;
 
             return Ok(ProjectInfo {
                 name,
@@ -120,7 +144,7 @@ impl ProjectInfo {
                 ..Default::default()
             });
         } else {
-            return Self::new(is_gen_ui);
+            return Self::new(is_gen_ui, path, taken);
         }
     }
     pub fn write_license<P>(&self, path: P) -> Result<(), Error>
@@ -0,0 +1,245 @@
+use std::path::Path;
+
+use gen_utils::compiler::Author;
+use inquire::validator::Validation;
+
+/// keywords of rust and names which are reserved by cargo or windows, can not be used as a crate name
+const RESERVED: &[&str] = &[
+    // [rust keywords] ---------------------------------------------------------------------------
+    "abstract",
+    "as",
+    "async",
+    "await",
+    "become",
+    "box",
+    "break",
+    "const",
+    "continue",
+    "crate",
+    "do",
+    "dyn",
+    "else",
+    "enum",
+    "extern",
+    "false",
+    "final",
+    "fn",
+    "for",
+    "if",
+    "impl",
+    "in",
+    "let",
+    "loop",
+    "macro",
+    "match",
+    "mod",
+    "move",
+    "mut",
+    "override",
+    "priv",
+    "pub",
+    "ref",
+    "return",
+    "self",
+    "static",
+    "struct",
+    "super",
+    "trait",
+    "true",
+    "try",
+    "type",
+    "typeof",
+    "unsafe",
+    "unsized",
+    "use",
+    "virtual",
+    "where",
+    "while",
+    "yield",
+    // [cargo] -----------------------------------------------------------------------------------
+    "core",
+    "std",
+    "alloc",
+    "proc_macro",
+    "test",
+    "deps",
+    "examples",
+    "build",
+    "incremental",
+    "target",
+    // [windows] ---------------------------------------------------------------------------------
+    // `com1` - `com9` and `lpt1` - `lpt9` are checked by `is_device`
+    "con",
+    "prn",
+    "aux",
+    "nul",
+];
+
+/// windows device names `com1` - `com9` and `lpt1` - `lpt9`
+fn is_device(name: &str) -> bool {
+    name.strip_prefix("com")
+        .or_else(|| name.strip_prefix("lpt"))
+        .is_some_and(|n| matches!(n.as_bytes(), [b'1'..=b'9']))
+}
+
+/// ## check the name of the crate (project or workspace)
+/// - ascii letters, digits, `_` and `-`, start with a letter or `_`
+/// - not reserved by rust, cargo or windows
+/// - not `src_gen_N`, which is the compiled project of GenUI
+pub fn crate_name(name: &str) -> Result<(), String> {
+    let Some(first) = name.chars().next() else {
+        return Err("name can not be empty".to_string());
+    };
+    if !(first.is_ascii_alphabetic() || first == '_') {
+        return Err(format!(
+            "name should start with a letter or `_`, got `{}`",
+            first
+        ));
+    }
+    if let Some(c) = name
+        .chars()
+        .find(|c| !(c.is_ascii_alphanumeric() || *c == '_' || *c == '-'))
+    {
+        return Err(format!(
+            "`{}` is not allowed, use ascii letters, digits, `_` or `-`",
+            c
+        ));
+    }
+    let lower = name.to_lowercase();
+    if RESERVED.contains(&lower.as_str()) || is_device(&lower) {
+        return Err(format!("`{}` is a reserved name", name));
+    }
+    if name
+        .strip_prefix("src_gen_")
+        .is_some_and(|index| index.parse::<usize>().is_ok())
+    {
+        return Err(format!(
+            "`{}` is reserved for the compiled project of GenUI",
+            name
+        ));
+    }
+    Ok(())
+}
+
+/// ## the dir `name` does not exist in the path
+pub fn not_exist(path: &Path, name: &str) -> Result<(), String> {
+    if path.join(name).exists() {
+        Err(format!("`{}` already exists in {}", name, path.display()))
+    } else {
+        Ok(())
+    }
+}
+
+/// ## the name is not used by other members
+pub fn not_taken(taken: &[String], name: &str) -> Result<(), String> {
+    if taken.iter().any(|member| member == name) {
+        Err(format!("`{}` is already a member of the workspace", name))
+    } else {
+        Ok(())
+    }
+}
+
+/// ## authors separated by `,`, each one is `name <email>` or `name`
+pub fn authors(authors: &str) -> Result<(), String> {
+    if authors.trim().is_empty() {
+        return Ok(());
+    }
+    for author in authors.split(',') {
+        if author.trim().parse::<Author>().is_err() {
+            return Err(format!(
+                "`{}` is not an author, use `name <email>`, name is a letter followed by letters, digits or `_`",
+                author.trim()
+            ));
+        }
+    }
+    Ok(())
+}
+
+/// ## semver version: `MAJOR.MINOR.PATCH` with optional `-pre` and `+build`
+pub fn version(version: &str) -> Result<(), String> {
+    let err = || {
+        Err(format!(
+            "`{}` is not a semver version, such as `0.1.0` or `1.0.0-alpha.1`",
+            version
+        ))
+    };
+    let (version, build) = match version.split_once('+') {
+        Some((version, build)) => (version, Some(build)),
+        None => (version, None),
+    };
+    let (core, pre) = match version.split_once('-') {
+        Some((core, pre)) => (core, Some(pre)),
+        None => (version, None),
+    };
+    let numbers = core.split('.').collect::<Vec<&str>>();
+    if numbers.len() != 3
+        || numbers.iter().any(|n| {
+            n.is_empty()
+                || !n.chars().all(|c| c.is_ascii_digit())
+                || (n.len() > 1 && n.starts_with('0'))
+        })
+    {
+        return err();
+    }
+    let identifiers = |s: &str| {
+        s.split('.')
+            .all(|id| !id.is_empty() && id.chars().all(|c| c.is_ascii_alphanumeric() || c == '-'))
+    };
+    if pre.is_some_and(|pre| !identifiers(pre)) || build.is_some_and(|build| !identifiers(build)) {
+        return err();
+    }
+    Ok(())
+}
+
+/// ## turn the checks into an inquire validator, the error is shown under the prompt
+pub fn validator<F>(
+    check: F,
+) -> impl Fn(&str) -> Result<Validation, inquire::CustomUserError> + Clone
+where
+    F: Fn(&str) -> Result<(), String> + Clone,
+{
+    move |input: &str| {
+        Ok(match check(input.trim()) {
+            Ok(_) => Validation::Valid,
+            Err(e) => Validation::Invalid(e.into()),
+        })
+    }
+}
+
+#[cfg(test)]
+mod test_validate {
+    use super::{authors, crate_name, version};
+
+    #[test]
+    fn names() {
+        assert!(crate_name("hello_world").is_ok());
+        assert!(crate_name("hello-world2").is_ok());
+        assert!(crate_name("").is_err());
+        assert!(crate_name("2hello").is_err());
+        assert!(crate_name("hello world").is_err());
+        assert!(crate_name("std").is_err());
+        assert!(crate_name("COM1").is_err());
+        assert!(crate_name("lpt9").is_err());
+        assert!(crate_name("com0").is_ok());
+        assert!(crate_name("com10").is_ok());
+        assert!(crate_name("src_gen_0").is_err());
+        assert!(crate_name("src_gen_x").is_ok());
+    }
+
+    #[test]
+    fn versions() {
+        for v in ["0.1.0", "1.20.3", "1.0.0-alpha.1", "1.0.0+build.5"] {
+            assert!(version(v).is_ok(), "{}", v);
+        }
+        for v in ["0.1", "01.0.0", "1.0.0-", "a.b.c", "1.0.0-al_pha"] {
+            assert!(version(v).is_err(), "{}", v);
+        }
+    }
+
+    #[test]
+    fn author_list() {
+        assert!(authors("John <john@example.com>, Mike").is_ok());
+        assert!(authors("John <john@example.com>,").is_err());
+        assert!(authors("<john@example.com>").is_err());
+    }
+}
@@ -1,7 +1,11 @@
-use super::ProjectInfo;
+use super::{
+    validate::{self, validator},
+    ProjectInfo,
+};
 use colored::Colorize;
 use gen_utils::error::Error;
 use inquire::{Confirm, Text};
+use std::path::Path;
 use toml_edit::{value, Array, DocumentMut, Item, Table};
 
 /// WorkspaceInfo
@@ -15,11 +19,20 @@ pub struct WorkspaceInfo {
 }
 
 impl WorkspaceInfo {
-    pub fn new(is_gen_ui: bool) -> Result<WorkspaceInfo, Error> {
+    /// ## ask the workspace info
+    /// - path: dir where the workspace is created
+    pub fn new(is_gen_ui: bool, path: &Path) -> Result<WorkspaceInfo, Error> {
         // [workspace name] ---------------------------------------------------------
+        let dir = path.to_path_buf();
         let name = Text::new("Input the name of the workspace:")
+            .with_validator(validator(move |name| {
+                validate::crate_name(name)?;
+                validate::not_exist(&dir, name)
+            }))
             .prompt()
-            .expect("Failed to get workspace name");
+            .map_err(|_| Error::from("Failed to get workspace name"))?
+            .trim()
+            .to_string();
 
         let mut workspace = WorkspaceInfo {
             name,
@@ -32,7 +45,12 @@ impl WorkspaceInfo {
                 "{}",
                 format!("============ Project{} ======================", index).yellow()
             );
-            let project = ProjectInfo::new(is_gen_ui)?;
+            let taken = workspace
+                .members
+                .iter()
+                .map(|member| member.name.to_string())
+                .collect::<Vec<String>>();
+            let project = ProjectInfo::new(is_gen_ui, None, &taken)?;
             workspace.members.push(project);
             index += 1;
             let continue_or = Confirm::new("Do you want to add another project?")
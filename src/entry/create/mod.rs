@@ -1,3 +1,5 @@
 mod info;
+mod template;
 
-pub use info::*;
\ No newline at end of file
+pub use info::*;
+pub use template::*;
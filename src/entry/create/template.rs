@@ -0,0 +1,567 @@
+use std::{
+    collections::BTreeMap,
+    path::{Component, Path, PathBuf},
+    process::Command,
+    str::FromStr,
+};
+
+use chrono::Datelike;
+use gen_utils::{common::fs, error::Error};
+use inquire::{Select, Text};
+use toml_edit::{DocumentMut, Item, Table};
+
+use crate::{
+    common::{builtin_template_file, TempDir, BUILTIN_TEMPLATE},
+    entry::{FrameworkType, ProjectInfo, WorkspaceInfo},
+};
+
+/// manifest file in the template dir
+pub const TEMPLATE_MANIFEST: &str = "template.toml";
+/// variables which are set by ract, prompts can not use these names
+const BUILTIN_VARS: [&str; 10] = [
+    "project_name",
+    "workspace_name",
+    "version",
+    "authors",
+    "description",
+    "license",
+    "keywords",
+    "framework",
+    "underlayer",
+    "year",
+];
+
+/// # Project Template
+/// files and dirs which are written into the new project by `ract create`, read from `template.toml`
+/// in the template dir (or a git repository, see [Template::load])
+/// ```toml
+/// [template]
+/// name = "starter"
+/// description = "Starter app of our company"
+/// # frameworks which the template supports, default is all
+/// frameworks = ["gen_ui"]
+///
+/// # ask the user when creating, the answer is the variable `${theme}`
+/// [[prompts]]
+/// name = "theme"
+/// message = "Theme of the app:"
+/// default = "dark"
+/// # select one of the options, otherwise input text
+/// options = ["dark", "light"]
+///
+/// [[dirs]]
+/// path = "resources"
+///
+/// [[files]]
+/// # path in the new project, variables can be used
+/// path = "views/root.gen"
+/// # file in the template dir (default is `path`), or `content = "..."` for inline content
+/// src = "gen_ui/views/root.gen"
+/// # only for the framework(s), default is all
+/// framework = "gen_ui"
+/// # `project` (default) or `workspace`, workspace files are written once into the workspace root
+/// scope = "project"
+/// # only when the variable is not empty, `false`, `no` or `0`
+/// when = "router"
+/// ```
+/// `${name}` in the paths and the text files is replaced by the variable, unknown variables are kept,
+/// see [Vars::project] for the variables set by ract
+#[derive(Debug, Clone)]
+pub struct Template {
+    pub name: String,
+    pub description: Option<String>,
+    /// empty means all frameworks
+    pub frameworks: Vec<FrameworkType>,
+    pub prompts: Vec<Prompt>,
+    pub entries: Vec<Entry>,
+    /// answers of the prompts, see [Template::prompt]
+    pub answers: Vars,
+}
+
+/// # Template Prompt
+#[derive(Debug, Clone)]
+pub struct Prompt {
+    pub name: String,
+    pub message: String,
+    pub default: Option<String>,
+    pub options: Vec<String>,
+}
+
+/// # Template Entry
+/// a file or a dir in the template
+#[derive(Debug, Clone)]
+pub struct Entry {
+    pub path: String,
+    /// `None` for a dir
+    pub content: Option<Vec<u8>>,
+    /// empty means all frameworks
+    pub frameworks: Vec<FrameworkType>,
+    pub scope: Scope,
+    pub when: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Scope {
+    /// relative to each project (member)
+    #[default]
+    Project,
+    /// relative to the workspace, a single project uses its own dir
+    Workspace,
+}
+
+impl Template {
+    /// ## the builtin template of ract, see [BUILTIN_TEMPLATE]
+    pub fn builtin() -> Self {
+        Self::parse(BUILTIN_TEMPLATE, |src| {
+            builtin_template_file(src)
+                .map(|content| content.as_bytes().to_vec())
+                .ok_or_else(|| Error::from(format!("builtin template has no file `{}`", src)))
+        })
+        .expect("builtin template is invalid")
+    }
+    /// ## load the template from a dir or a git url
+    /// a git repository is cloned (`--depth 1`) into a temporary dir, files are read into memory
+    /// so that the temporary dir can be removed at once
+    pub fn load(source: &str) -> Result<Self, Error> {
+        let dir = Path::new(source);
+        if dir.is_dir() {
+            return Self::read(dir);
+        }
+        if !is_git_url(source) {
+            return Err(Error::from(format!(
+                "template `{}` is neither a dir nor a git url",
+                source
+            )));
+        }
+
+        // git clones into a dir which does not exist
+        let temp = TempDir::new("ract_template")?;
+        let repo = temp.path().join("repo");
+        let status = Command::new("git")
+            .args(["clone", "--depth", "1", source])
+            .arg(repo.as_path())
+            .status()
+            .map_err(|e| Error::from(format!("failed to clone template `{}`: {}", source, e)))?;
+        if status.success() {
+            Self::read(repo.as_path())
+        } else {
+            Err(Error::from(format!(
+                "failed to clone template `{}`: {}",
+                source, status
+            )))
+        }
+    }
+    /// ## read the template from the dir which contains [TEMPLATE_MANIFEST]
+    pub fn read<P>(dir: P) -> Result<Self, Error>
+    where
+        P: AsRef<Path>,
+    {
+        let manifest = dir.as_ref().join(TEMPLATE_MANIFEST);
+        if !manifest.is_file() {
+            return Err(Error::from(format!(
+                "template has no {}: {}",
+                TEMPLATE_MANIFEST,
+                fs::path_to_str(&manifest)
+            )));
+        }
+        let content = fs::read(manifest.as_path())?;
+        Self::parse(&content, |src| {
+            let path = relative(src).map(|src| dir.as_ref().join(src))?;
+            std::fs::read(path.as_path()).map_err(|e| {
+                Error::from(format!(
+                    "can not read template file {}: {}",
+                    fs::path_to_str(&path),
+                    e
+                ))
+            })
+        })
+    }
+    /// ## parse the manifest
+    /// - read: read the content of `src` in the template
+    pub fn parse<F>(manifest: &str, read: F) -> Result<Self, Error>
+    where
+        F: Fn(&str) -> Result<Vec<u8>, Error>,
+    {
+        let toml = manifest
+            .parse::<DocumentMut>()
+            .map_err(|e| Error::from(format!("{} is invalid: {}", TEMPLATE_MANIFEST, e)))?;
+        // [template] ------------------------------------------------------------------------------------
+        let template = toml
+            .get("template")
+            .and_then(Item::as_table)
+            .ok_or_else(|| Error::from(format!("{} has no [template]", TEMPLATE_MANIFEST)))?;
+        let name = string(template, "template", "name")?
+            .ok_or_else(|| Error::from("[template] name is required"))?;
+        let description = string(template, "template", "description")?;
+        let frameworks = read_frameworks(template, "template", "frameworks")?;
+        // [[prompts]] -----------------------------------------------------------------------------------
+        let mut prompts: Vec<Prompt> = vec![];
+        for table in tables(&toml, "prompts")? {
+            let prompt = Prompt {
+                name: string(table, "prompts", "name")?
+                    .ok_or_else(|| Error::from("[[prompts]] name is required"))?,
+                message: string(table, "prompts", "message")?
+                    .ok_or_else(|| Error::from("[[prompts]] message is required"))?,
+                default: string(table, "prompts", "default")?,
+                options: strings(table, "prompts", "options")?,
+            };
+            if BUILTIN_VARS.contains(&prompt.name.as_str())
+                || prompts.iter().any(|p| p.name == prompt.name)
+            {
+                return Err(Error::from(format!(
+                    "[[prompts]] name `{}` is builtin or used by another prompt",
+                    prompt.name
+                )));
+            }
+            prompts.push(prompt);
+        }
+        // [[dirs]] and [[files]] ------------------------------------------------------------------------
+        let mut entries = vec![];
+        for (key, is_file) in [("dirs", false), ("files", true)] {
+            for table in tables(&toml, key)? {
+                let path = string(table, key, "path")?
+                    .ok_or_else(|| Error::from(format!("[[{}]] path is required", key)))?;
+                let _ = relative(&path)?;
+                let content = if is_file {
+                    Some(match string(table, key, "content")? {
+                        Some(content) => content.into_bytes(),
+                        None => read(&string(table, key, "src")?.unwrap_or_else(|| path.clone()))?,
+                    })
+                } else {
+                    None
+                };
+                let scope = match string(table, key, "scope")?.as_deref() {
+                    None | Some("project") => Scope::Project,
+                    Some("workspace") => Scope::Workspace,
+                    Some(scope) => {
+                        return Err(Error::from(format!(
+                            "[[{}]] scope should be `project` or `workspace`, got `{}`",
+                            key, scope
+                        )))
+                    }
+                };
+                entries.push(Entry {
+                    path,
+                    content,
+                    frameworks: read_frameworks(table, key, "framework")?,
+                    scope,
+                    when: string(table, key, "when")?,
+                });
+            }
+        }
+
+        Ok(Self {
+            name,
+            description,
+            frameworks,
+            prompts,
+            entries,
+            answers: Vars::default(),
+        })
+    }
+    /// ## check the template supports the framework
+    pub fn supports(&self, framework: FrameworkType) -> bool {
+        self.frameworks.is_empty() || self.frameworks.contains(&framework)
+    }
+    /// ## ask the prompts of the template, the answers are kept in [Template::answers]
+    pub fn prompt(&mut self) -> Result<(), Error> {
+        for prompt in self.prompts.iter() {
+            let answer = if prompt.options.is_empty() {
+                let mut text = Text::new(&prompt.message);
+                if let Some(default) = prompt.default.as_ref() {
+                    text = text.with_default(default);
+                }
+                text.prompt()
+            } else {
+                let cursor = prompt
+                    .default
+                    .as_ref()
+                    .and_then(|default| prompt.options.iter().position(|o| o == default))
+                    .unwrap_or(0);
+                Select::new(&prompt.message, prompt.options.clone())
+                    .with_starting_cursor(cursor)
+                    .prompt()
+            }
+            .map_err(|e| Error::from(e.to_string()))?;
+            self.answers.set(&prompt.name, answer);
+        }
+        Ok(())
+    }
+    /// ## write the dirs and files of the scope into the path
+    /// - vars: variables set by ract, answers of the prompts are added
+    pub fn apply<P>(
+        &self,
+        path: P,
+        scope: Scope,
+        framework: FrameworkType,
+        vars: &Vars,
+    ) -> Result<(), Error>
+    where
+        P: AsRef<Path>,
+    {
+        let mut vars = vars.clone();
+        for (name, value) in self.answers.0.iter() {
+            vars.set(name, value.to_string());
+        }
+
+        for entry in self.entries.iter().filter(|entry| {
+            entry.scope == scope
+                && (entry.frameworks.is_empty() || entry.frameworks.contains(&framework))
+                && entry.when.as_ref().is_none_or(|when| vars.is_true(when))
+        }) {
+            let to = path.as_ref().join(relative(&vars.substitute(&entry.path))?);
+            match entry.content.as_ref() {
+                None => std::fs::create_dir_all(to.as_path()).map_err(|e| e.to_string())?,
+                Some(content) => {
+                    if let Some(parent) = to.parent() {
+                        std::fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+                    }
+                    // binary files are written as they are
+                    let content = match std::str::from_utf8(content) {
+                        Ok(text) => vars.substitute(text).into_bytes(),
+                        Err(_) => content.to_vec(),
+                    };
+                    std::fs::write(to.as_path(), content).map_err(|e| e.to_string())?;
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+/// # Template Variables
+/// `${name}` in the template is replaced by the value
+#[derive(Debug, Clone, Default)]
+pub struct Vars(BTreeMap<String, String>);
+
+impl Vars {
+    /// ## variables of the project
+    /// - workspace: name of the workspace, default is the project name
+    pub fn project(info: &ProjectInfo, workspace: Option<&str>, framework: FrameworkType) -> Self {
+        let mut vars = Self::default();
+        vars.set("project_name", info.name.to_string());
+        vars.set(
+            "workspace_name",
+            workspace.unwrap_or(info.name.as_str()).to_string(),
+        );
+        vars.set("version", info.version.to_string());
+        vars.set(
+            "authors",
+            info.authors
+                .as_ref()
+                .map(|authors| {
+                    authors
+                        .iter()
+                        .map(ToString::to_string)
+                        .collect::<Vec<String>>()
+                        .join(", ")
+                })
+                .unwrap_or_default(),
+        );
+        vars.set("description", info.description.clone().unwrap_or_default());
+        vars.set("license", info.license.to_string());
+        vars.set("keywords", info.keywords.join(", "));
+        vars.set("framework", framework.to_string());
+        vars.set(
+            "underlayer",
+            info.underlayer
+                .map(|underlayer| underlayer.to_string())
+                .unwrap_or_default(),
+        );
+        vars.set("year", chrono::Local::now().year().to_string());
+        vars
+    }
+    /// ## variables of the workspace, project variables are the first member's
+    pub fn workspace(info: &WorkspaceInfo, framework: FrameworkType) -> Self {
+        let mut vars = info
+            .members
+            .first()
+            .map(|member| Self::project(member, Some(&info.name), framework))
+            .unwrap_or_default();
+        vars.set("workspace_name", info.name.to_string());
+        vars.set("framework", framework.to_string());
+        vars
+    }
+    pub fn set(&mut self, name: &str, value: String) {
+        self.0.insert(name.to_string(), value);
+    }
+    pub fn get(&self, name: &str) -> Option<&str> {
+        self.0.get(name).map(String::as_str)
+    }
+    /// value is not empty, `false`, `no` or `0`
+    pub fn is_true(&self, name: &str) -> bool {
+        self.get(name).is_some_and(|value| {
+            !matches!(
+                value.trim().to_lowercase().as_str(),
+                "" | "false" | "no" | "0"
+            )
+        })
+    }
+    /// ## replace `${name}` by the variables, unknown variables are kept
+    pub fn substitute(&self, content: &str) -> String {
+        let mut res = String::with_capacity(content.len());
+        let mut rest = content;
+        while let Some(start) = rest.find("${") {
+            res.push_str(&rest[..start]);
+            let after = &rest[start + 2..];
+            match after
+                .find('}')
+                .and_then(|end| self.get(&after[..end]).map(|value| (end, value)))
+            {
+                Some((end, value)) => {
+                    res.push_str(value);
+                    rest = &after[end + 1..];
+                }
+                None => {
+                    res.push_str("${");
+                    rest = after;
+                }
+            }
+        }
+        res.push_str(rest);
+        res
+    }
+}
+
+/// the path should be relative and stay in the project
+fn relative(path: &str) -> Result<PathBuf, Error> {
+    let path = PathBuf::from(path);
+    if path
+        .components()
+        .all(|component| matches!(component, Component::Normal(_) | Component::CurDir))
+    {
+        Ok(path)
+    } else {
+        Err(Error::from(format!(
+            "template path `{}` should be relative and in the template",
+            fs::path_to_str(&path)
+        )))
+    }
+}
+
+fn is_git_url(source: &str) -> bool {
+    ["https://", "http://", "ssh://", "git@", "git://"]
+        .iter()
+        .any(|prefix| source.starts_with(prefix))
+        || source.ends_with(".git")
+}
+
+fn tables<'a>(toml: &'a DocumentMut, key: &str) -> Result<Vec<&'a Table>, Error> {
+    match toml.get(key) {
+        None => Ok(vec![]),
+        Some(item) => item
+            .as_array_of_tables()
+            .map(|tables| tables.iter().collect())
+            .ok_or_else(|| Error::from(format!("[[{}]] must be an array of tables", key))),
+    }
+}
+
+fn string(table: &Table, name: &str, key: &str) -> Result<Option<String>, Error> {
+    table.get(key).map_or(Ok(None), |item| {
+        item.as_str()
+            .map(|s| Some(s.to_string()))
+            .ok_or_else(|| Error::from(format!("`{}` in [{}] must be a string", key, name)))
+    })
+}
+
+fn strings(table: &Table, name: &str, key: &str) -> Result<Vec<String>, Error> {
+    match table.get(key) {
+        None => Ok(vec![]),
+        Some(item) => match (item.as_str(), item.as_array()) {
+            (Some(s), _) => Ok(vec![s.to_string()]),
+            (_, Some(array)) => array
+                .iter()
+                .map(|value| {
+                    value.as_str().map(ToString::to_string).ok_or_else(|| {
+                        Error::from(format!("`{}` in [{}] must be strings", key, name))
+                    })
+                })
+                .collect(),
+            _ => Err(Error::from(format!(
+                "`{}` in [{}] must be a string or an array of strings",
+                key, name
+            ))),
+        },
+    }
+}
+
+fn read_frameworks(table: &Table, name: &str, key: &str) -> Result<Vec<FrameworkType>, Error> {
+    strings(table, name, key)?
+        .iter()
+        .map(|framework| FrameworkType::from_str(framework))
+        .collect()
+}
+
+#[cfg(test)]
+mod test_template {
+    use crate::{common::TempDir, entry::FrameworkType};
+
+    use super::{Scope, Template, Vars};
+
+    #[test]
+    fn builtin() {
+        let template = Template::builtin();
+        assert!(
+            template.supports(FrameworkType::GenUI) && template.supports(FrameworkType::Makepad)
+        );
+
+        let dir = TempDir::new("ract_template").unwrap();
+        let mut vars = Vars::default();
+        vars.set("project_name", "hello".to_string());
+        template
+            .apply(dir.path(), Scope::Project, FrameworkType::Makepad, &vars)
+            .unwrap();
+        let main_rs = std::fs::read_to_string(dir.path().join("src/main.rs")).unwrap();
+
+        assert!(main_rs.contains("hello::app::app_main()"));
+        assert!(!dir.path().join("views").exists());
+    }
+
+    #[test]
+    fn custom() {
+        let manifest = r#"
+[template]
+name = "starter"
+frameworks = ["gen_ui"]
+
+[[prompts]]
+name = "router"
+message = "Use router?"
+options = ["yes", "no"]
+
+[[files]]
+path = "views/${project_name}.gen"
+content = "<template>${project_name} ${unknown}</template>"
+
+[[files]]
+path = "router.toml"
+src = "router.toml"
+when = "router"
+"#;
+        let mut template =
+            Template::parse(manifest, |src| Ok(format!("# {}", src).into_bytes())).unwrap();
+        assert!(!template.supports(FrameworkType::Makepad));
+
+        let dir = TempDir::new("ract_template").unwrap();
+        let mut vars = Vars::default();
+        vars.set("project_name", "hello".to_string());
+        template.answers.set("router", "no".to_string());
+        template
+            .apply(dir.path(), Scope::Project, FrameworkType::GenUI, &vars)
+            .unwrap();
+        let view = std::fs::read_to_string(dir.path().join("views/hello.gen")).unwrap();
+
+        assert_eq!(view, "<template>hello ${unknown}</template>");
+        assert!(!dir.path().join("router.toml").exists());
+        assert!(Template::parse(
+            "[template]\nname = \"a\"\n[[files]]\npath = \"../a\"\ncontent = \"\"",
+            |_| Ok(vec![])
+        )
+        .is_err());
+        assert!(Template::parse(
+            "[template]\nname = \"a\"\n[[prompts]]\nname = \"year\"\nmessage = \"?\"",
+            |_| Ok(vec![])
+        )
+        .is_err());
+    }
+}
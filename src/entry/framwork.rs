@@ -6,7 +6,7 @@ use gen_utils::{
 use std::{fmt::Display, str::FromStr};
 use toml_edit::{value, Item, Table};
 
-#[derive(Debug, Clone, Copy, Default)]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
 pub enum FrameworkType {
     #[default]
     GenUI,
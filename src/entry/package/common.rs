@@ -100,11 +100,13 @@ impl TryFrom<&Item> for Size {
 ///
 /// One of the following:
 ///
+/// ```text
 ///     "editor" CFBundleTypeRole.Editor. Files can be read and edited.
 ///     "viewer" CFBundleTypeRole.Viewer. Files can be read.
 ///     "shell" CFBundleTypeRole.Shell
 ///     "qLGenerator" CFBundleTypeRole.QLGenerator
 ///     "none" CFBundleTypeRole.None
+/// ```
 ///
 /// macOS-only*. Corresponds to CFBundleTypeRole
 #[derive(Debug, Clone, Default, Copy)]
@@ -159,6 +161,7 @@ impl TryFrom<&Value> for BundleTypeRole {
 ///
 /// One of the following:
 ///
+/// ```text
 ///     "all" All available package formats for the current platform. See [PackageFormat::platform_all]
 ///     "default" The default list of package formats for the current platform. See [PackageFormat::platform_default]
 ///     "app" The macOS application bundle (.app).
@@ -168,6 +171,7 @@ impl TryFrom<&Value> for BundleTypeRole {
 ///     "deb" The Linux Debian package (.deb).
 ///     "appimage" The Linux AppImage package (.AppImage).
 ///     "pacman" The Linux Pacman package (.tar.gz and PKGBUILD)
+/// ```
 ///
 /// Types of supported packages by cargo-packager.
 #[derive(Debug, Clone, Default)]
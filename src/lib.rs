@@ -0,0 +1,101 @@
+//! # Ract
+//!
+//! The project model of ract as a library: the `ract` binary is a thin layer over this crate.
+//!
+//! ## Configs
+//!
+//! | file | type | load | save |
+//! | --- | --- | --- | --- |
+//! | `.ract` | [RactToml] | `RactToml::try_from(&path)` | `ToToml::write` |
+//! | `gen_ui.toml` | [GenUIConf] | `GenUIConf::new(project)` | `ToToml::write` |
+//! | `[package.metadata.packager]` in Cargo.toml | [PackageConf] | `PackageConf::from_cargo_toml(path)` | [package::generate] |
+//! | `env.toml` | [ChainEnvToml] | `ChainEnvToml::try_from(ChainEnvToml::path()?)` | `ChainEnvToml::write` |
+//!
+//! configs are validated when they are loaded, an [Error] is returned if the config is invalid.
+//! `.ract` and `gen_ui.toml` which are loaded from a file only write the changed keys back, so comments,
+//! key order and keys which ract does not model are kept.
+//!
+//! ## Compile
+//!
+//! ```no_run
+//! use ract::{compiler, RactToml};
+//!
+//! # fn main() -> Result<(), ract::Error> {
+//! let path = std::path::PathBuf::from("/path/to/workspace");
+//! let ract_toml = RactToml::try_from(&path.join(".ract"))?;
+//! // logs and diagnostics are sent to the callback instead of the terminal
+//! compiler::set_sink(|event| println!("{:?}", event));
+//! let mut compiler = compiler::Compiler::new(path.as_path(), &ract_toml.first_compile()?)?;
+//! compiler.on_updated(|| println!("updated"));
+//! compiler.build()?;
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! ## Layered Config
+//!
+//! compiler settings are layered as: defaults < gen_ui.toml < `GENUI_*` env vars < CLI flags.
+//! [compiler::Compiler::new] applies the env vars, use [compiler::Compiler::overrides] for the flags,
+//! and [compiler::Layers] tells where each effective value comes from.
+//!
+//! ## Underlayers
+//!
+//! `[compiler] target` in gen_ui.toml selects the underlayer compiler by name. Besides the builtin
+//! [Underlayer]s, a compiler can be added in the process by [compiler::register_underlayer], otherwise
+//! the target runs as an [compiler::ExternalCompiler] which speaks JSON-RPC by stdin/stdout.
+//!
+//! `[[compiler.outputs]]` compiles the same source by more underlayers, each into its own crate
+//! (see [compiler::OutputConf]). all outputs share one watcher, but each output has its own cache and
+//! diagnostics, a failed output never stops the others.
+//!
+//! ## Package
+//!
+//! see [package], the steps are the same as `ract package` without prompts
+mod app;
+mod cli;
+mod common;
+mod entry;
+mod log;
+mod service;
+
+rust_i18n::i18n!("locales", fallback = ["en_US", "zh_CN"]);
+
+pub use entry::{
+    ChainEnvToml, FrameworkType, GenUIConf, Language, Member, PackageConf, RactToml, Resource,
+    Underlayer,
+};
+pub use gen_utils::{common::ToToml, error::Error};
+pub use log::LogLevel;
+
+/// entry of the `ract` binary, the CLI is not a part of the library API
+#[doc(hidden)]
+pub fn cli_main() -> Result<(), Box<dyn std::error::Error>> {
+    cli::main().map_err(Into::into)
+}
+
+/// # Compiler
+/// compile GenUI projects, use [set_sink] to receive logs and diagnostics by a callback
+pub mod compiler {
+    pub use crate::entry::{ConfOverrides, Layer, Layers, OutputConf, WatcherConf};
+    pub use crate::entry::{
+        Cache, Compiler, ConfWarning, Daemon, DaemonStatus, Diagnostic, DiagnosticFormat, Diagnostics,
+        Excludes, Metrics, Phase, Severity, SourceLocation, SourceMap,
+    };
+    pub use crate::entry::{
+        register_underlayer, unregister_underlayer, CustomConf, ExternalCompiler,
+        UnderlayerFactory,
+    };
+    pub use crate::log::compiler::{clear_sink, set_sink, CompilerEvent};
+}
+
+/// # Package
+/// package the project by cargo-packager
+///
+/// 1. [check_or_install_packager]
+/// 2. [generate] the package configuration (or read the generated one by [package_info])
+/// 3. [pack]
+pub mod package {
+    pub use crate::service::package::{
+        check_or_install_packager, generate, pack, package_info, PackageInfo,
+    };
+}
@@ -0,0 +1,67 @@
+use std::{error::Error, fmt::Display};
+
+use rust_i18n::t;
+
+use crate::entry::Language;
+
+use super::LogExt;
+
+#[derive(Debug, Clone)]
+pub enum BuildLogs {
+    Desc,
+    /// build the member
+    Build(String),
+    /// dry run the member
+    DryRun(String),
+    /// build the compiled project by cargo
+    Cargo(String),
+    /// compiled project is the same as the generated one
+    UpToDate(String),
+    /// compiled project is different from the generated one
+    Differences {
+        target: String,
+        added: usize,
+        removed: usize,
+        changed: usize,
+    },
+    Complete,
+    Error(String),
+}
+
+impl Display for BuildLogs {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.t(Language::En).as_ref())
+    }
+}
+
+impl Error for BuildLogs {}
+
+impl LogExt for BuildLogs {
+    fn t(&self, lang: Language) -> std::borrow::Cow<'_, str> {
+        let lang_str = lang.as_str();
+        match self {
+            BuildLogs::Desc => t!("build.desc", locale = lang_str),
+            BuildLogs::Build(member) => t!("build.build", locale = lang_str, member = member),
+            BuildLogs::DryRun(member) => t!("build.dry_run", locale = lang_str, member = member),
+            BuildLogs::Cargo(target) => t!("build.cargo", locale = lang_str, target = target),
+            BuildLogs::UpToDate(target) => {
+                t!("build.up_to_date", locale = lang_str, target = target)
+            }
+            BuildLogs::Differences {
+                target,
+                added,
+                removed,
+                changed,
+            } => t!(
+                "build.differences",
+                locale = lang_str,
+                target = target,
+                added = added,
+                removed = removed,
+                changed = changed
+            ),
+            BuildLogs::Complete => t!("build.complete", locale = lang_str),
+            BuildLogs::Error(reason) => t!("build.error", locale = lang_str, reason = reason),
+        }
+    }
+}
@@ -9,7 +9,7 @@
 //!
 //! Example:
 //!
-//! ```rust
+//! ```text
 //! GenUI-Compiler :: [2024-06-29T08:53:57Z] :: INFO >>>
 //!
 //!      _/_/_/  _/_/_/_/  _/      _/  _/    _/  _/_/_/
@@ -24,7 +24,7 @@
 //!
 //! The GenUI Logger provides detailed information about the state of various services. Here are some log examples:
 //!
-//! ```rust
+//! ```text
 //! GenUI-Compiler :: [2024-06-29T08:53:57Z] :: INFO >>> 🔧 Log Service is starting... Log entries will be available after the `app event::Change` occurs!
 //! GenUI-Compiler :: [2024-06-29T08:53:57Z] :: INFO >>> 🔧 Source Generator Service started successfully!
 //! GenUI-Compiler :: [2024-06-29T08:53:57Z] :: INFO >>> ✅ Cache Service: Cache file written successfully!
@@ -36,15 +36,19 @@
 //!
 //! The logger also tracks and displays compile timings, helping you monitor the compilation process:
 //!
-//! ```rust
+//! ```text
 //! GenUI-Compiler :: [2024-06-28T19:09:24Z] :: INFO >>> File "E:\\Rust\\try\\makepad\\Gen-UI\\examples\\gen_makepad_simple\\ui\\views\\root.gen" compiled successfully.
 //! GenUI-Compiler :: [2024-06-28T19:09:24Z] :: INFO >>> ✅ Cache Service: Cache file written successfully!
 //! GenUI-Compiler :: [2024-06-28T19:09:24Z] :: INFO >>> File "E:\\Rust\\try\\makepad\\Gen-UI\\examples\\gen_makepad_simple\\ui\\views\\root.gen" compiled successfully.
 //! ```
 
-use std::{error::Error, fmt::Display, path::PathBuf};
+use std::{error::Error, fmt::Display, path::PathBuf, sync::RwLock};
 
-use crate::{common::LOGO, log::level::LevelColord};
+use crate::{
+    common::LOGO,
+    entry::Diagnostic,
+    log::level::LevelColord,
+};
 use colored::Colorize;
 use env_logger::{Builder, WriteStyle};
 use gen_utils::common::time::local_time_default;
@@ -55,9 +59,8 @@ use std::io::Write;
 use super::{LogExt, LogLevel};
 
 /// # Init Log
-/// init GenUI log service. It will read the system environment variable `GENUI_LOGO` and `GENUI_LOG_LEVEL` to set the log level and print the logo.
-/// If the system environment variable is not set, it will read the configuration file in the project root path.
-/// If the configuration file is not found, it will use the default value.
+/// init GenUI log service with the effective log level of the compiler, which is layered as:
+/// default < `[compiler] log_level` in gen_ui.toml < `GENUI_LOG_LEVEL` < `--log-level` (`GENUI_LOGO` works the same way, see `Layer`).
 /// > This function should be called before any other service is started.
 pub fn init(log_level: LogLevel) -> () {
     // [init log env] -----------------------------------------------------------------------------------------
@@ -67,7 +70,8 @@ pub fn init(log_level: LogLevel) -> () {
     // [build log] -----------------------------------------------------------------------------------------
     let mut builder = Builder::new();
 
-    builder
+    // `try_init`: more than one compiler may be created in a process (such as `ract build`)
+    let _ = builder
         .filter_level(log_level.into())
         .write_style(WriteStyle::Always)
         .format(|buf, record| {
@@ -83,11 +87,56 @@ pub fn init(log_level: LogLevel) -> () {
                 record.args()
             )
         })
-        .init();
+        .try_init();
 
     CompilerLogs::LogInit.compiler().info();
 }
 
+/// # Compiler Event
+/// logs and diagnostics reported by the compiler, see [set_sink]
+#[derive(Debug, Clone, Copy)]
+pub enum CompilerEvent<'a> {
+    Log(LogLevel, &'a str),
+    Diagnostic(&'a Diagnostic),
+}
+
+type Sink = Box<dyn Fn(CompilerEvent<'_>) + Send + Sync>;
+
+static SINK: RwLock<Option<Sink>> = RwLock::new(None);
+
+/// ## receive compiler events by the callback instead of printing them
+/// the sink is shared by all compilers in the process (the logo is not printed when it is set).
+/// use [clear_sink] to print to the terminal again
+pub fn set_sink<F>(f: F)
+where
+    F: Fn(CompilerEvent<'_>) + Send + Sync + 'static,
+{
+    if let Ok(mut sink) = SINK.write() {
+        sink.replace(Box::new(f));
+    }
+}
+
+pub fn clear_sink() {
+    if let Ok(mut sink) = SINK.write() {
+        sink.take();
+    }
+}
+
+pub fn has_sink() -> bool {
+    SINK.read().is_ok_and(|sink| sink.is_some())
+}
+
+/// send the event to the sink, return false if the sink is not set
+pub fn send(event: CompilerEvent<'_>) -> bool {
+    match SINK.read().as_deref() {
+        Ok(Some(sink)) => {
+            sink(event);
+            true
+        }
+        _ => false,
+    }
+}
+
 pub struct CompilerLogger {
     pub output: String,
 }
@@ -99,15 +148,21 @@ impl CompilerLogger {
         }
     }
     pub fn info(&self) -> () {
-        info!("{}", self.output.white());
+        if !send(CompilerEvent::Log(LogLevel::Info, &self.output)) {
+            info!("{}", self.output.white());
+        }
     }
 
     pub fn warn(&self) -> () {
-        warn!("{}", self.output.bright_yellow());
+        if !send(CompilerEvent::Log(LogLevel::Warn, &self.output)) {
+            warn!("{}", self.output.bright_yellow());
+        }
     }
 
     pub fn error(&self) -> () {
-        error!("{}", self.output.bright_red());
+        if !send(CompilerEvent::Log(LogLevel::Error, &self.output)) {
+            error!("{}", self.output.bright_red());
+        }
     }
     #[allow(dead_code)]
     pub fn error_and_exit(&self) -> ! {
@@ -135,8 +190,48 @@ pub enum CompilerLogs {
     LogInit,
     Logo,
     WatcherInit(PathBuf),
-    Compiled(PathBuf),
+    /// compiled file and the time it took
+    Compiled(PathBuf, String),
     WriteCache,
+    /// incremental compile or clean compile
+    Incremental(bool),
+    /// summary of `compile_all`
+    CompileAll {
+        changed: usize,
+        removed: usize,
+        unchanged: usize,
+    },
+    /// output of a deleted source is removed before the first compile
+    Pruned { source: PathBuf, output: PathBuf },
+    /// summary of diagnostics in a compile pass
+    Summary {
+        errors: usize,
+        warnings: usize,
+    },
+    /// timings of `compile_all`
+    Metrics {
+        total: String,
+        phases: String,
+        slowest: String,
+    },
+    /// metrics can not be written
+    MetricsFailed(String),
+    /// can not write the source map, the compiler keeps running
+    SourceMapFailed(String),
+    /// problem in gen_ui.toml, see `ConfWarning`
+    ConfWarning(String),
+    /// run a hook in `[compiler.hooks]`
+    Hook(String),
+    /// hook failed, the watcher keeps running
+    HookFailed(String),
+    /// ask to repair the workspace manifest
+    WorkspaceConfirm { path: PathBuf, steps: String },
+    /// workspace manifest repaired
+    Workspace(String),
+    /// JSON-RPC daemon is listening on the address
+    Daemon(String),
+    /// extra output (`[[compiler.outputs]]`) failed, other outputs keep compiling
+    OutputFailed { output: String, reason: String },
 }
 
 impl Display for CompilerLogs {
@@ -163,14 +258,85 @@ impl LogExt for CompilerLogs {
                 locale = lang_str,
                 path = path_buf.display()
             ),
-            CompilerLogs::Compiled(path_buf) => {
+            CompilerLogs::Compiled(path_buf, time) => {
                 t!(
                     "compiler.compiled",
                     locale = lang_str,
-                    path = path_buf.display()
+                    path = path_buf.display(),
+                    time = time
                 )
             },
             CompilerLogs::WriteCache => t!("compiler.write_cache", locale = lang_str),
+            CompilerLogs::Incremental(incremental) => {
+                if *incremental {
+                    t!("compiler.incremental", locale = lang_str)
+                } else {
+                    t!("compiler.clean", locale = lang_str)
+                }
+            }
+            CompilerLogs::CompileAll {
+                changed,
+                removed,
+                unchanged,
+            } => t!(
+                "compiler.compile_all",
+                locale = lang_str,
+                changed = changed,
+                removed = removed,
+                unchanged = unchanged
+            ),
+            CompilerLogs::Summary { errors, warnings } => t!(
+                "compiler.summary",
+                locale = lang_str,
+                errors = errors,
+                warnings = warnings
+            ),
+            CompilerLogs::Metrics {
+                total,
+                phases,
+                slowest,
+            } => t!(
+                "compiler.metrics",
+                locale = lang_str,
+                total = total,
+                phases = phases,
+                slowest = slowest
+            ),
+            CompilerLogs::MetricsFailed(reason) => {
+                t!("compiler.metrics_failed", locale = lang_str, reason = reason)
+            }
+            CompilerLogs::Pruned { source, output } => t!(
+                "compiler.pruned",
+                locale = lang_str,
+                source = source.display(),
+                output = output.display()
+            ),
+            CompilerLogs::SourceMapFailed(reason) => {
+                t!("compiler.source_map_failed", locale = lang_str, reason = reason)
+            }
+            CompilerLogs::ConfWarning(warning) => {
+                t!("compiler.conf_warning", locale = lang_str, warning = warning)
+            }
+            CompilerLogs::Hook(hook) => t!("compiler.hook", locale = lang_str, hook = hook),
+            CompilerLogs::HookFailed(reason) => {
+                t!("compiler.hook_failed", locale = lang_str, reason = reason)
+            }
+            CompilerLogs::WorkspaceConfirm { path, steps } => t!(
+                "compiler.workspace_confirm",
+                locale = lang_str,
+                path = path.display(),
+                steps = steps
+            ),
+            CompilerLogs::Workspace(steps) => {
+                t!("compiler.workspace", locale = lang_str, steps = steps)
+            }
+            CompilerLogs::Daemon(addr) => t!("compiler.daemon", locale = lang_str, addr = addr),
+            CompilerLogs::OutputFailed { output, reason } => t!(
+                "compiler.output_failed",
+                locale = lang_str,
+                output = output,
+                reason = reason
+            ),
         }
     }
 }
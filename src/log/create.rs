@@ -2,7 +2,7 @@ use super::LogExt;
 use rust_i18n::t;
 use std::{error::Error, fmt::Display};
 
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone)]
 pub enum CreateLogs {
     Workspace,
     Unsupported,
@@ -12,6 +12,9 @@ pub enum CreateLogs {
     Confirm,
     Cancel,
     CargoErr,
+    Member,
+    /// a custom template is used (`--template`)
+    Template { name: String, description: String },
 }
 
 impl Display for CreateLogs {
@@ -34,6 +37,13 @@ impl LogExt for CreateLogs {
             CreateLogs::Confirm => t!("create.confirm", locale = lang),
             CreateLogs::Cancel => t!("create.cancel", locale = lang),
             CreateLogs::CargoErr => t!("create.cargo_err", locale = lang),
+            CreateLogs::Member => t!("create.member", locale = lang),
+            CreateLogs::Template { name, description } => t!(
+                "create.template",
+                locale = lang,
+                name = name,
+                description = description
+            ),
         }
     }
 }
@@ -0,0 +1,41 @@
+use std::{error::Error, fmt::Display};
+
+use rust_i18n::t;
+
+use crate::entry::Language;
+
+use super::LogExt;
+
+#[derive(Debug, Clone)]
+pub enum InfoLogs {
+    Desc,
+    /// compiler config of the member
+    Member(String),
+    /// problem in gen_ui.toml
+    Warning(String),
+    Complete,
+    Error(String),
+}
+
+impl Display for InfoLogs {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.t(Language::En).as_ref())
+    }
+}
+
+impl Error for InfoLogs {}
+
+impl LogExt for InfoLogs {
+    fn t(&self, lang: Language) -> std::borrow::Cow<'_, str> {
+        let lang_str = lang.as_str();
+        match self {
+            InfoLogs::Desc => t!("info.desc", locale = lang_str),
+            InfoLogs::Member(member) => t!("info.member", locale = lang_str, member = member),
+            InfoLogs::Warning(warning) => {
+                t!("info.warning", locale = lang_str, warning = warning)
+            }
+            InfoLogs::Complete => t!("info.complete", locale = lang_str),
+            InfoLogs::Error(reason) => t!("info.error", locale = lang_str, reason = reason),
+        }
+    }
+}
@@ -1,15 +1,18 @@
 mod add;
+mod build;
 mod check;
 pub mod compiler;
 mod config;
 mod create;
 #[allow(unused)]
 pub mod error;
+mod info;
 mod init;
 mod install;
 mod level;
 mod package;
 mod run;
+mod snapshot;
 mod uninstall;
 mod wasm;
 
@@ -21,12 +24,14 @@ use std::{
 };
 
 pub use add::AddLogs;
+pub use build::BuildLogs;
 pub use check::CheckLogs;
 use chrono::{DateTime, Local};
 use colored::Colorize;
 pub use config::ConfigLogs;
 pub use create::CreateLogs;
 use gen_utils::common::string::FixedString;
+pub use info::InfoLogs;
 pub use init::InitLogs;
 pub use install::InstallLogs;
 pub use level::LogLevel;
@@ -38,6 +43,7 @@ use ratatui::{
 pub use run::{ProjectLogs, StudioLogs};
 use rust_i18n::t;
 
+pub use snapshot::SnapshotLogs;
 pub use uninstall::UninstallLogs;
 pub use wasm::WasmLogs;
 
@@ -81,7 +87,7 @@ impl LogItem {
     }
     /// ## print log item
     /// display as:
-    /// ```
+    /// ```text
     /// Ract [${fmt_date_time}]: [${level}] >>> ${msg}
     /// ```
     pub fn log(&self) -> () {
@@ -189,6 +195,9 @@ pub enum CommandType {
     Install,
     Wasm,
     Run,
+    Build,
+    Snapshot,
+    Info,
     Pkg,
     Add,
     Update,
@@ -203,7 +212,10 @@ impl From<&Commands> for CommandType {
             Commands::Create(_) => CommandType::Create,
             Commands::Check => CommandType::Check,
             Commands::Install => CommandType::Install,
-            Commands::Run => CommandType::Run,
+            Commands::Run(_) => CommandType::Run,
+            Commands::Build(_) => CommandType::Build,
+            Commands::Snapshot(_) => CommandType::Snapshot,
+            Commands::Info(_) => CommandType::Info,
             Commands::Init => CommandType::Init,
             Commands::Config => CommandType::Config,
             Commands::Studio => CommandType::Studio,
@@ -228,6 +240,9 @@ impl Display for CommandType {
             CommandType::Studio => "STUDIO",
             CommandType::Wasm => "WASM",
             CommandType::Run => "RUN",
+            CommandType::Build => "BUILD",
+            CommandType::Snapshot => "SNAPSHOT",
+            CommandType::Info => "INFO",
             CommandType::Pkg => "PKG",
             CommandType::Add => "ADD",
             CommandType::Update => "UPDATE",
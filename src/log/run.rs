@@ -85,6 +85,16 @@ pub enum ProjectLogs {
     Start,
     Stop,
     Error(String),
+    /// rebuild the app after changes
+    Rebuild,
+    /// app restarted with the new build
+    Restart,
+    /// build failed, the last good build keeps running
+    BuildFailed,
+    /// watch the project for changes
+    Watch(String),
+    /// source map can not be read, diagnostics are not mapped to the `.gen` files
+    SourceMapFailed(String),
 }
 
 impl Display for ProjectLogs {
@@ -103,6 +113,15 @@ impl LogExt for ProjectLogs {
             ProjectLogs::Start => t!("project.start", locale = lang_str),
             ProjectLogs::Stop => t!("project.stop", locale = lang_str),
             ProjectLogs::Error(reason) => t!("project.err", locale = lang_str, reason = reason),
+            ProjectLogs::Rebuild => t!("project.rebuild", locale = lang_str),
+            ProjectLogs::Restart => t!("project.restart", locale = lang_str),
+            ProjectLogs::BuildFailed => t!("project.build_failed", locale = lang_str),
+            ProjectLogs::Watch(path) => t!("project.watch", locale = lang_str, path = path),
+            ProjectLogs::SourceMapFailed(reason) => t!(
+                "project.source_map_failed",
+                locale = lang_str,
+                reason = reason
+            ),
         }
     }
 }
@@ -0,0 +1,77 @@
+use std::{error::Error, fmt::Display};
+
+use rust_i18n::t;
+
+use crate::entry::Language;
+
+use super::LogExt;
+
+#[derive(Debug, Clone)]
+pub enum SnapshotLogs {
+    Desc,
+    /// record the snapshot of the member
+    Record(String),
+    /// snapshot is stored
+    Recorded {
+        snapshot: String,
+        files: usize,
+    },
+    /// check the snapshot of the member
+    Check(String),
+    /// generated output is the same as the snapshot
+    Matched(String),
+    /// generated output is different from the snapshot
+    Mismatched {
+        member: String,
+        added: usize,
+        removed: usize,
+        changed: usize,
+    },
+    Complete,
+    Error(String),
+}
+
+impl Display for SnapshotLogs {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.t(Language::En).as_ref())
+    }
+}
+
+impl Error for SnapshotLogs {}
+
+impl LogExt for SnapshotLogs {
+    fn t(&self, lang: Language) -> std::borrow::Cow<'_, str> {
+        let lang_str = lang.as_str();
+        match self {
+            SnapshotLogs::Desc => t!("snapshot.desc", locale = lang_str),
+            SnapshotLogs::Record(member) => {
+                t!("snapshot.record", locale = lang_str, member = member)
+            }
+            SnapshotLogs::Recorded { snapshot, files } => t!(
+                "snapshot.recorded",
+                locale = lang_str,
+                snapshot = snapshot,
+                files = files
+            ),
+            SnapshotLogs::Check(member) => t!("snapshot.check", locale = lang_str, member = member),
+            SnapshotLogs::Matched(member) => {
+                t!("snapshot.matched", locale = lang_str, member = member)
+            }
+            SnapshotLogs::Mismatched {
+                member,
+                added,
+                removed,
+                changed,
+            } => t!(
+                "snapshot.mismatched",
+                locale = lang_str,
+                member = member,
+                added = added,
+                removed = removed,
+                changed = changed
+            ),
+            SnapshotLogs::Complete => t!("snapshot.complete", locale = lang_str),
+            SnapshotLogs::Error(reason) => t!("snapshot.error", locale = lang_str, reason = reason),
+        }
+    }
+}
@@ -0,0 +1,278 @@
+use std::{
+    collections::BTreeSet,
+    path::{Path, PathBuf},
+};
+
+use colored::Colorize;
+use gen_utils::{common::fs, error::Error};
+use walkdir::WalkDir;
+
+use crate::entry::SOURCE_MAP;
+
+/// lines of context around changes in a hunk
+const CONTEXT: usize = 3;
+/// max size of the lcs table, larger files are diffed as a whole replacement
+const MAX_TABLE: usize = 16 * 1024 * 1024;
+
+/// # Change
+/// a changed file between the current compiled project and the generated one, path is relative
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Change {
+    Added(PathBuf),
+    Removed(PathBuf),
+    Changed(PathBuf),
+}
+
+impl Change {
+    pub fn path(&self) -> &Path {
+        match self {
+            Change::Added(path) | Change::Removed(path) | Change::Changed(path) => path.as_path(),
+        }
+    }
+    /// ## one line summary, eg: `M src/views/home.rs`
+    pub fn summary(&self) -> String {
+        let path = fs::path_to_str(self.path());
+        match self {
+            Change::Added(_) => format!("{} {}", "A".green(), path),
+            Change::Removed(_) => format!("{} {}", "D".red(), path),
+            Change::Changed(_) => format!("{} {}", "M".yellow(), path),
+        }
+    }
+    /// ## unified diff of the change
+    /// - current: the current compiled project
+    /// - generated: the generated compiled project
+    pub fn diff<P>(&self, current: P, generated: P) -> Result<String, Error>
+    where
+        P: AsRef<Path>,
+    {
+        let read = |root: &Path| -> Result<Option<Vec<u8>>, Error> {
+            let path = root.join(self.path());
+            if path.is_file() {
+                std::fs::read(path)
+                    .map(Some)
+                    .map_err(|e| e.to_string().into())
+            } else {
+                Ok(None)
+            }
+        };
+        let (old, new) = (read(current.as_ref())?, read(generated.as_ref())?);
+        let name = fs::path_to_str(self.path());
+        let old_name = old
+            .as_ref()
+            .map_or("/dev/null".to_string(), |_| format!("a/{}", name));
+        let new_name = new
+            .as_ref()
+            .map_or("/dev/null".to_string(), |_| format!("b/{}", name));
+
+        let old = String::from_utf8(old.unwrap_or_default());
+        let new = String::from_utf8(new.unwrap_or_default());
+        match (old, new) {
+            (Ok(old), Ok(new)) => Ok(unified(&old_name, &new_name, &old, &new)),
+            _ => Ok(format!(
+                "Binary files {} and {} differ\n",
+                old_name, new_name
+            )),
+        }
+    }
+}
+
+/// ## compare the current compiled project with the generated one
+/// `target` dir, `Cargo.lock` and the source map are not compared, changes are sorted by path
+pub fn compare<P>(current: P, generated: P) -> Result<Vec<Change>, Error>
+where
+    P: AsRef<Path>,
+{
+    let (current, generated) = (current.as_ref(), generated.as_ref());
+    let (olds, news) = (files(current), files(generated));
+
+    let mut changes = vec![];
+    for path in olds.union(&news) {
+        match (olds.contains(path), news.contains(path)) {
+            (true, false) => changes.push(Change::Removed(path.to_path_buf())),
+            (false, true) => changes.push(Change::Added(path.to_path_buf())),
+            _ => {
+                let old = std::fs::read(current.join(path)).map_err(|e| e.to_string())?;
+                let new = std::fs::read(generated.join(path)).map_err(|e| e.to_string())?;
+                if old != new {
+                    changes.push(Change::Changed(path.to_path_buf()));
+                }
+            }
+        }
+    }
+
+    Ok(changes)
+}
+
+/// relative paths of all files in the project
+fn files(root: &Path) -> BTreeSet<PathBuf> {
+    let ignores = [
+        root.join("target"),
+        root.join("Cargo.lock"),
+        root.join(SOURCE_MAP),
+    ];
+    WalkDir::new(root)
+        .into_iter()
+        .filter_entry(|e| !ignores.iter().any(|ignore| e.path() == ignore))
+        .filter_map(|e| e.ok())
+        .filter(|e| e.file_type().is_file())
+        .filter_map(|e| e.path().strip_prefix(root).ok().map(Path::to_path_buf))
+        .collect()
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Edit<'a> {
+    Equal(&'a str),
+    Delete(&'a str),
+    Insert(&'a str),
+}
+
+/// ## line edits from old to new (longest common subsequence)
+fn edits<'a>(old: &[&'a str], new: &[&'a str]) -> Vec<Edit<'a>> {
+    // common prefix and suffix are always equal, only diff the middle part
+    let prefix = old.iter().zip(new).take_while(|(a, b)| a == b).count();
+    let suffix = old[prefix..]
+        .iter()
+        .rev()
+        .zip(new[prefix..].iter().rev())
+        .take_while(|(a, b)| a == b)
+        .count();
+    let (a, b) = (
+        &old[prefix..old.len() - suffix],
+        &new[prefix..new.len() - suffix],
+    );
+
+    let mut res = old[..prefix]
+        .iter()
+        .map(|line| Edit::Equal(line))
+        .collect::<Vec<Edit>>();
+
+    if (a.len() + 1) * (b.len() + 1) > MAX_TABLE {
+        res.extend(a.iter().map(|line| Edit::Delete(line)));
+        res.extend(b.iter().map(|line| Edit::Insert(line)));
+    } else {
+        // lcs[i * w + j] is the lcs length of a[i..] and b[j..]
+        let w = b.len() + 1;
+        let mut lcs = vec![0_u32; (a.len() + 1) * w];
+        for i in (0..a.len()).rev() {
+            for j in (0..b.len()).rev() {
+                lcs[i * w + j] = if a[i] == b[j] {
+                    lcs[(i + 1) * w + j + 1] + 1
+                } else {
+                    lcs[(i + 1) * w + j].max(lcs[i * w + j + 1])
+                };
+            }
+        }
+
+        let (mut i, mut j) = (0, 0);
+        while i < a.len() && j < b.len() {
+            if a[i] == b[j] {
+                res.push(Edit::Equal(a[i]));
+                i += 1;
+                j += 1;
+            } else if lcs[(i + 1) * w + j] >= lcs[i * w + j + 1] {
+                res.push(Edit::Delete(a[i]));
+                i += 1;
+            } else {
+                res.push(Edit::Insert(b[j]));
+                j += 1;
+            }
+        }
+        res.extend(a[i..].iter().map(|line| Edit::Delete(line)));
+        res.extend(b[j..].iter().map(|line| Edit::Insert(line)));
+    }
+
+    res.extend(
+        old[old.len() - suffix..]
+            .iter()
+            .map(|line| Edit::Equal(line)),
+    );
+    res
+}
+
+/// ## unified diff of two texts, return empty string if they are the same
+pub fn unified(old_name: &str, new_name: &str, old: &str, new: &str) -> String {
+    let old_lines = old.lines().collect::<Vec<&str>>();
+    let new_lines = new.lines().collect::<Vec<&str>>();
+    let edits = edits(&old_lines, &new_lines);
+
+    // [group changes into hunks] ------------------------------------------------------------------------------
+    let mut hunks: Vec<(usize, usize)> = vec![];
+    for (index, _) in edits
+        .iter()
+        .enumerate()
+        .filter(|(_, edit)| !matches!(edit, Edit::Equal(_)))
+    {
+        let start = index.saturating_sub(CONTEXT);
+        let end = (index + CONTEXT + 1).min(edits.len());
+        match hunks.last_mut() {
+            Some(last) if start <= last.1 => last.1 = end,
+            _ => hunks.push((start, end)),
+        }
+    }
+    if hunks.is_empty() {
+        return String::new();
+    }
+
+    let mut res = format!(
+        "{}\n{}\n",
+        format!("--- {}", old_name).bold(),
+        format!("+++ {}", new_name).bold()
+    );
+    for (start, end) in hunks {
+        let before = &edits[..start];
+        let hunk = &edits[start..end];
+        let count = |edits: &[Edit], old: bool| {
+            edits
+                .iter()
+                .filter(|edit| match edit {
+                    Edit::Equal(_) => true,
+                    Edit::Delete(_) => old,
+                    Edit::Insert(_) => !old,
+                })
+                .count()
+        };
+        // line number starts from 1, an empty range points to the line before it
+        let range = |old: bool| {
+            let (before, len) = (count(before, old), count(hunk, old));
+            let start = if len == 0 { before } else { before + 1 };
+            format!("{},{}", start, len)
+        };
+
+        res.push_str(&format!(
+            "{}\n",
+            format!("@@ -{} +{} @@", range(true), range(false)).cyan()
+        ));
+        for edit in hunk {
+            let line = match edit {
+                Edit::Equal(line) => format!(" {}", line).normal(),
+                Edit::Delete(line) => format!("-{}", line).red(),
+                Edit::Insert(line) => format!("+{}", line).green(),
+            };
+            res.push_str(&format!("{}\n", line));
+        }
+    }
+
+    res
+}
+
+#[cfg(test)]
+mod test_diff {
+    use super::unified;
+
+    #[test]
+    fn hunks() {
+        colored::control::set_override(false);
+        let old = "a\nb\nc\nd\ne\nf\ng\nh\ni\n";
+        let new = "a\nb\nc\nD\ne\nf\ng\nh\ni\nj\n";
+
+        assert_eq!(
+            unified("a/x.rs", "b/x.rs", old, new),
+            "--- a/x.rs\n+++ b/x.rs\n@@ -1,9 +1,10 @@\n a\n b\n c\n-d\n+D\n e\n f\n g\n h\n i\n+j\n"
+        );
+        assert_eq!(
+            unified("/dev/null", "b/x.rs", "", "a\n"),
+            "--- /dev/null\n+++ b/x.rs\n@@ -0,0 +1,1 @@\n+a\n"
+        );
+        assert!(unified("a/x.rs", "b/x.rs", old, old).is_empty());
+    }
+}
@@ -0,0 +1,332 @@
+pub mod diff;
+
+use std::{env::current_dir, path::Path, process::exit};
+
+use clap::Args;
+use gen_utils::{
+    common::{fs, ToToml},
+    compiler::CompilerImpl,
+    error::Error,
+};
+use walkdir::WalkDir;
+
+use crate::{
+    common::TempDir,
+    entry::{
+        is_cache_file, Compiler, ConfOverrides, DiagnosticFormat, FrameworkType, Language, Member,
+        RactToml,
+    },
+    log::{BuildLogs, LogExt},
+    service::run::runner,
+};
+
+#[derive(Args, Debug)]
+pub struct BuildArgs {
+    /// Compile into a temporary dir and compare it with the current compiled project, nothing is overwritten
+    #[arg(long, default_value = "false")]
+    pub dry_run: bool,
+    /// Print the unified diff of each changed file (with `--dry-run`)
+    #[arg(long, default_value = "false", requires = "dry_run")]
+    pub diff: bool,
+    /// Keep watching and compiling after the first build (the first member only)
+    #[arg(short, long, default_value = "false", conflicts_with = "dry_run")]
+    pub watch: bool,
+    /// Serve the compiler state by JSON-RPC on a localhost port or `unix:<socket>` (with `--watch`)
+    #[arg(long, value_name = "ADDR", requires = "watch")]
+    pub rpc: Option<String>,
+    /// Only compile the GenUI project, do not run `cargo build` on the compiled project
+    #[arg(long, default_value = "false")]
+    pub no_cargo: bool,
+    /// Keep the outputs of sources which are deleted while ract is not running
+    #[arg(long, default_value = "false")]
+    pub no_prune: bool,
+    /// Fail if gen_ui.toml has unknown keys, values of the wrong type or broken plugins
+    #[arg(long, default_value = "false")]
+    pub strict: bool,
+    #[command(flatten)]
+    pub overrides: ConfOverrides,
+}
+
+impl BuildArgs {
+    /// ## build the GenUI project once without watching
+    /// exit with `1` if `--dry-run` finds differences, `2` if build failed
+    pub fn run(&self, lang: Language) {
+        BuildLogs::Desc.info(lang).multi().print();
+        let path = current_dir().unwrap();
+        match self.build(path.as_path(), lang) {
+            Ok(true) => {
+                BuildLogs::Complete.success(lang).print();
+            }
+            Ok(false) => exit(1),
+            Err(e) => {
+                BuildLogs::Error(e.to_string()).error(lang).print();
+                exit(2);
+            }
+        }
+    }
+
+    /// return the compiled projects are up to date or not (always true if not dry run)
+    fn build(&self, path: &Path, lang: Language) -> Result<bool, Error> {
+        let ract_toml: RactToml = (&RactToml::read(path.join(".ract"))?).try_into()?;
+        if let FrameworkType::Makepad = ract_toml.target {
+            return Err(Error::from(
+                "`ract build` only supports GenUI projects, use `cargo build` for Makepad projects",
+            ));
+        }
+        let compiles = ract_toml
+            .compiles()
+            .ok_or_else(|| Error::from("can not get compile members from .ract"))?;
+
+        if self.watch {
+            // the watcher blocks, so only the first member is watched (same as `ract run`)
+            let member = compiles[0];
+            BuildLogs::Build(fs::path_to_str(&member.source))
+                .info(lang)
+                .print();
+            let mut compiler = Compiler::new(path, member)?;
+            compiler.prune = !self.no_prune;
+            compiler.strict = self.strict;
+            compiler.overrides(&self.overrides)?;
+            if let Some(rpc) = self.rpc.as_ref() {
+                compiler.serve(rpc)?;
+            }
+            compiler.run();
+            return Ok(true);
+        }
+
+        let mut up_to_date = true;
+        for member in compiles {
+            if self.dry_run {
+                BuildLogs::DryRun(fs::path_to_str(&member.source))
+                    .info(lang)
+                    .print();
+                up_to_date &= self.dry_run(path, member, lang)?;
+            } else {
+                BuildLogs::Build(fs::path_to_str(&member.source))
+                    .info(lang)
+                    .print();
+                let mut compiler = Compiler::new(path, member)?;
+                compiler.prune = !self.no_prune;
+                compiler.strict = self.strict;
+                compiler.overrides(&self.overrides)?;
+                compiler.build()?;
+                if !self.no_cargo {
+                    cargo_build(path, member, compiler.conf.compiler.diagnostics, lang)?;
+                }
+            }
+        }
+        Ok(up_to_date)
+    }
+
+    /// ## compile the member into a temporary workspace and compare with the current compiled project
+    fn dry_run(&self, path: &Path, member: &Member, lang: Language) -> Result<bool, Error> {
+        // temporary workspace is removed when it is dropped
+        let temp = TempDir::new("ract_dry_run")?;
+        let changes = dry_run_in(temp.path(), path, member, &self.overrides)?;
+        // [print diff] ----------------------------------------------------------------------------------------
+        if self.diff {
+            let current = path.join(member.target.as_path());
+            let generated = temp.path().join(member.target.as_path());
+            for change in changes.iter() {
+                print!("{}", change.diff(current.as_path(), generated.as_path())?);
+            }
+        }
+
+        // [summary] -------------------------------------------------------------------------------------------
+        let target = fs::path_to_str(&member.target);
+        if changes.is_empty() {
+            BuildLogs::UpToDate(target).success(lang).print();
+            return Ok(true);
+        }
+        for change in changes.iter() {
+            println!("{}", change.summary());
+        }
+        let count = |f: fn(&diff::Change) -> bool| changes.iter().filter(|c| f(c)).count();
+        BuildLogs::Differences {
+            target,
+            added: count(|c| matches!(c, diff::Change::Added(_))),
+            removed: count(|c| matches!(c, diff::Change::Removed(_))),
+            changed: count(|c| matches!(c, diff::Change::Changed(_))),
+        }
+        .warning(lang)
+        .print();
+        Ok(false)
+    }
+}
+
+/// ## build the compiled project by cargo, diagnostics are mapped to the source project
+/// the compiled project is a member of the workspace, its package name is the dir name
+fn cargo_build(
+    path: &Path,
+    member: &Member,
+    format: DiagnosticFormat,
+    lang: Language,
+) -> Result<(), Error> {
+    let package = member
+        .target
+        .file_name()
+        .map(|name| name.to_string_lossy().to_string());
+    BuildLogs::Cargo(fs::path_to_str(&member.target))
+        .info(lang)
+        .print();
+    if runner::build(path, package.as_ref(), format, lang)? {
+        Ok(())
+    } else {
+        Err(Error::from(format!(
+            "`cargo build` failed for {}",
+            fs::path_to_str(&member.target)
+        )))
+    }
+}
+
+/// ## compile the member in the temporary workspace and compare with the current compiled project
+fn dry_run_in(
+    temp: &Path,
+    path: &Path,
+    member: &Member,
+    overrides: &ConfOverrides,
+) -> Result<Vec<diff::Change>, Error> {
+    compile_in(temp, path, member, overrides)?;
+    diff::compare(
+        path.join(member.target.as_path()),
+        temp.join(member.target.as_path()),
+    )
+}
+
+/// ## compile the member in the temporary workspace
+/// the temporary workspace should be an empty dir (see [TempDir]), the source project is copied (without
+/// cache and build output), hooks are not run and nothing in the workspace is changed, the compiled project
+/// is at `temp.join(member.target)`. the overrides are applied as the real build does
+pub fn compile_in(
+    temp: &Path,
+    path: &Path,
+    member: &Member,
+    overrides: &ConfOverrides,
+) -> Result<(), Error> {
+    // [copy source project] -------------------------------------------------------------------------------
+    let source = path.join(member.source.as_path());
+    let target = source.join("target");
+    for entry in WalkDir::new(source.as_path())
+        .into_iter()
+        .filter_entry(|e| e.path() != target && !is_cache_file(e.path()))
+        .filter_map(|e| e.ok())
+        .filter(|e| e.file_type().is_file())
+    {
+        let to = temp.join(member.source.as_path()).join(
+            entry
+                .path()
+                .strip_prefix(source.as_path())
+                .map_err(|e| e.to_string())?,
+        );
+        fs::copy_file(entry.path(), to.as_path())?;
+    }
+    if path.join(".gitignore").is_file() {
+        fs::copy_file(path.join(".gitignore"), temp.join(".gitignore"))?;
+    }
+    // [workspace] -----------------------------------------------------------------------------------------
+    fs::write(
+        temp.join("Cargo.toml").as_path(),
+        "[workspace]\nmembers = []\nresolver = \"2\"\n",
+    )?;
+    // [compile] -------------------------------------------------------------------------------------------
+    let mut compiler = Compiler::new(temp, member)?;
+    compiler.overrides(overrides)?;
+    compiler.conf.compiler.hooks = Default::default();
+    compiler.conf.compiler.incremental = false;
+    compiler.conf.compiler.logo = false;
+    compiler.build()
+}
+
+#[cfg(test)]
+mod test_build {
+    use std::{collections::HashMap, path::PathBuf};
+
+    use gen_utils::{
+        compiler::{CompilerImpl, Executor},
+        error::Error,
+    };
+
+    use super::compile_in;
+    use crate::{
+        common::TempDir,
+        entry::{register_underlayer, unregister_underlayer, ConfOverrides, Member},
+    };
+
+    /// underlayer which compiles nothing, only the files copied by the compiler are in the output
+    struct Noop;
+
+    impl CompilerImpl for Noop {
+        fn execute_auxiliaries(&mut self, _executor: Executor) {}
+        fn init(&mut self) -> Result<(), Error> {
+            Ok(())
+        }
+        fn send_plugins(&mut self) -> Result<(), Error> {
+            Ok(())
+        }
+        fn recv_plugins(
+            &mut self,
+            _plugins: Option<&HashMap<String, PathBuf>>,
+        ) -> Result<(), Error> {
+            Ok(())
+        }
+        fn before_compile(&mut self) -> Result<(), Error> {
+            Ok(())
+        }
+        fn after_compile(&mut self) -> Result<(), Error> {
+            Ok(())
+        }
+        fn compile(&mut self, _path: PathBuf) -> Result<(), Error> {
+            Ok(())
+        }
+        fn remove(&mut self, _path: PathBuf) -> Result<Option<Vec<PathBuf>>, Error> {
+            Ok(None)
+        }
+        fn update(&mut self) -> Result<(), Error> {
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn dry_run_excludes() {
+        register_underlayer("noop", |_, _| Ok(Box::new(Noop))).unwrap();
+        let tmp = TempDir::new("ract_build").unwrap();
+        let workspace = tmp.path();
+        let source = workspace.join("hello");
+        std::fs::create_dir_all(source.join("src")).unwrap();
+        std::fs::create_dir_all(source.join("drafts")).unwrap();
+        std::fs::write(
+            workspace.join("Cargo.toml"),
+            "[workspace]\nmembers = []\nresolver = \"2\"\n",
+        )
+        .unwrap();
+        std::fs::write(
+            source.join("Cargo.toml"),
+            "[package]\nname = \"hello\"\nversion = \"0.1.0\"\nedition = \"2021\"\n",
+        )
+        .unwrap();
+        std::fs::write(
+            source.join("gen_ui.toml"),
+            "[compiler]\ntarget = \"noop\"\nlogo = false\n\n[noop]\n",
+        )
+        .unwrap();
+        std::fs::write(source.join("src").join("main.rs"), "fn main() {}\n").unwrap();
+        std::fs::write(source.join("drafts").join("todo.txt"), "todo").unwrap();
+
+        let member = Member {
+            source: "hello".into(),
+            target: "src_gen_0".into(),
+        };
+        let overrides = ConfOverrides {
+            excludes: vec!["drafts/**".to_string()],
+            ..Default::default()
+        };
+        let temp = TempDir::new("ract_dry_run").unwrap();
+        let res = compile_in(temp.path(), workspace, &member, &overrides);
+        unregister_underlayer("noop");
+        res.unwrap();
+
+        let output = temp.path().join("src_gen_0");
+        assert!(output.join("src").join("main.rs").is_file());
+        assert!(!output.join("drafts").join("todo.txt").exists());
+    }
+}
@@ -0,0 +1,124 @@
+use std::path::{Path, PathBuf};
+
+use gen_utils::{
+    common::{fs, ToToml},
+    error::Error,
+};
+use inquire::Confirm;
+use toml_edit::DocumentMut;
+
+use crate::{
+    entry::{
+        check_workspace, gen_ui, makepad, repair_workspace, FrameworkType, Language, Member,
+        ProjectInfo, RactToml, Template,
+    },
+    log::{CreateLogs, LogExt},
+};
+
+/// ## add a new member into the workspace at the path
+/// - GenUI: the member is appended to `members` of `.ract` with the next `src_gen_N` target
+/// - Makepad: the workspace should have `[workspace]` in Cargo.toml
+///
+/// both add the member into `workspace.members` of the workspace Cargo.toml
+pub fn add<P>(path: P, template: &Template, lang: Language) -> Result<(), Error>
+where
+    P: AsRef<Path>,
+{
+    let path = path.as_ref();
+    let ract_path = path.join(".ract");
+    if !ract_path.exists() {
+        return Err(Error::from(format!(
+            "{} is not a project created by ract, can not find .ract",
+            fs::path_to_str(path)
+        )));
+    }
+    let mut ract_toml: RactToml = (&RactToml::read(ract_path.as_path())?).try_into()?;
+    let cargo_path = path.join("Cargo.toml");
+    let mut cargo_toml = fs::read(cargo_path.as_path())?
+        .parse::<DocumentMut>()
+        .map_err(|e| Error::from(e.to_string()))?;
+    if ract_toml.target == FrameworkType::Makepad && cargo_toml.get("workspace").is_none() {
+        return Err(Error::from(
+            "the Makepad project is not a workspace, can not add a member into it",
+        ));
+    }
+    let workspace = path
+        .file_name()
+        .map(|name| name.to_string_lossy().to_string())
+        .unwrap_or_default();
+
+    // [project info] --------------------------------------------------------------------------
+    let taken = ract_toml
+        .members
+        .iter()
+        .flatten()
+        .map(|member| fs::path_to_str(&member.source))
+        .collect::<Vec<String>>();
+    let info = ProjectInfo::new(ract_toml.target.is_gen_ui(), Some(path), &taken)?;
+    // [create the member] ---------------------------------------------------------------------
+    match ract_toml.target {
+        FrameworkType::GenUI => {
+            let members = ract_toml.members.get_or_insert_with(Vec::new);
+            let member = Member::from((&info, next_target(members)));
+            gen_ui::create_project(path, &info, &workspace, template)?;
+            members.push(member);
+            let index = members.len() - 1;
+            if Confirm::new("Compile the new member when running the workspace?")
+                .with_default(true)
+                .prompt()
+                .map_err(|e| Error::from(e.to_string()))?
+            {
+                // without `compiles` only the first member is compiled
+                let compiles = ract_toml.compiles.get_or_insert_with(|| vec![0]);
+                if !compiles.contains(&index) {
+                    compiles.push(index);
+                }
+            }
+            // [.ract] ------------------------------------------------------------------------
+            ract_toml.write(ract_path.as_path())?;
+        }
+        FrameworkType::Makepad => {
+            let _ = makepad::create_project(path, &info, Some(&workspace), template)?;
+        }
+    }
+    // [workspace Cargo.toml] ------------------------------------------------------------------
+    let repairs = check_workspace(Some(&cargo_toml), &info.name)?;
+    repair_workspace(&mut cargo_toml, &repairs);
+    fs::write(cargo_path.as_path(), &cargo_toml.to_string())?;
+
+    CreateLogs::Member.success(lang).print();
+    Ok(())
+}
+
+/// the next `src_gen_N` which is not used by other members
+fn next_target(members: &[Member]) -> usize {
+    (members.len()..)
+        .find(|index| {
+            let target = PathBuf::from(format!("src_gen_{}", index));
+            members.iter().all(|member| member.target != target)
+        })
+        .unwrap_or(members.len())
+}
+
+#[cfg(test)]
+mod test_member {
+    use std::path::PathBuf;
+
+    use crate::entry::Member;
+
+    use super::next_target;
+
+    #[test]
+    fn next() {
+        let member = |source: &str, target: &str| Member {
+            source: PathBuf::from(source),
+            target: PathBuf::from(target),
+        };
+        assert_eq!(next_target(&[]), 0);
+        assert_eq!(next_target(&[member("hello", "src_gen_0")]), 1);
+        assert_eq!(
+            next_target(&[member("hello", "src_gen_0"), member("world", "src_gen_2")]),
+            3
+        );
+    }
+}
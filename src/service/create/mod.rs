@@ -1,11 +1,13 @@
+mod member;
+
 use std::{path::PathBuf, process::exit, str::FromStr};
 
 use crate::{
-    entry::{FrameworkType, Language, ProjectInfoType},
+    entry::{FrameworkType, Language, ProjectInfoType, Template},
     log::{CreateLogs, LogExt, LogItem},
 };
 
-use clap::Args;
+use clap::{Args, Subcommand};
 use gen_utils::error::Error;
 use inquire::{Confirm, Select};
 
@@ -18,6 +20,11 @@ use super::check::current_states;
 /// 
 /// ```shell
 /// ract create
+/// # create from a template dir or a git repository, see `Template`
+/// ract create --template ./my_template
+/// ract create --template https://github.com/xxx/my_template.git
+/// # add a new project into the workspace at the current directory
+/// ract create member
 /// ```
 #[derive(Args, Debug)]
 pub struct CreateArgs {
@@ -26,6 +33,17 @@ pub struct CreateArgs {
     /// Path to create the project
     #[arg(short, long, default_value = ".")]
     pub path: PathBuf,
+    /// Template dir or git url which contains `template.toml`, default is the builtin template
+    #[arg(short, long)]
+    pub template: Option<String>,
+    #[command(subcommand)]
+    pub action: Option<CreateAction>,
+}
+
+#[derive(Subcommand, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CreateAction {
+    /// Add a new project into the existing workspace at `--path`
+    Member,
 }
 
 impl CreateArgs {
@@ -41,15 +59,20 @@ impl CreateArgs {
                     CreateLogs::Unsupported.error(lang).print();
                     exit(2);
                 }
-                self.create_project(lang).map_or_else(
-                    |e| {
-                        LogItem::error(e.to_string()).print();
-                        exit(2);
-                    },
-                    |_| {
-                        CreateLogs::Confirm.success(lang).print();
-                    },
-                )
+                self.template(lang)
+                    .and_then(|template| match self.action {
+                        Some(CreateAction::Member) => self.create_member(lang, &template),
+                        None => self.create_project(lang, &template),
+                    })
+                    .map_or_else(
+                        |e| {
+                            LogItem::error(e.to_string()).print();
+                            exit(2);
+                        },
+                        |_| {
+                            CreateLogs::Confirm.success(lang).print();
+                        },
+                    )
             }
             Err(e) => {
                 LogItem::error(e.to_string()).print();
@@ -57,22 +80,58 @@ impl CreateArgs {
             }
         }
     }
-    fn create_project(&self, lang: Language) -> Result<(), Error> {
+    /// load the template before asking anything, so that a bad template fails at once
+    fn template(&self, lang: Language) -> Result<Template, Error> {
+        match self.template.as_ref() {
+            Some(source) => {
+                let template = Template::load(source)?;
+                CreateLogs::Template {
+                    name: template.name.to_string(),
+                    description: template
+                        .description
+                        .as_deref()
+                        .unwrap_or(source)
+                        .to_string(),
+                }
+                .info(lang)
+                .print();
+                Ok(template)
+            }
+            None => Ok(Template::builtin()),
+        }
+    }
+    fn create_project(&self, lang: Language, template: &Template) -> Result<(), Error> {
         match self.path.canonicalize() {
             Ok(path) => {
                 // [select framework] ----------------------------------------------------------------
+                let options = FrameworkType::options()
+                    .into_iter()
+                    .filter(|option| {
+                        FrameworkType::from_str(option)
+                            .is_ok_and(|framework| template.supports(framework))
+                    })
+                    .collect::<Vec<_>>();
+                if options.is_empty() {
+                    return Err(Error::from(format!(
+                        "template `{}` does not support any framework",
+                        template.name
+                    )));
+                }
                 let framwork = Select::new(
                     "Which framework template do you want to create?",
-                    FrameworkType::options(),
+                    options,
                 )
                 .with_starting_cursor(0)
                 .prompt()
                 .map_err(|e| Error::from(e.to_string()))?;
                 let framework = FrameworkType::from_str(&framwork)?;
                 // [get project info] ----------------------------------------------------------------
-                let project_info_type = ProjectInfoType::new(framework)?;
+                let project_info_type = ProjectInfoType::new(framework, path.as_path())?;
                 // [get generate] --------------------------------------------------------------------
                 let mut generator = project_info_type.create(path.as_path(), framework);
+                // [template prompts] ----------------------------------------------------------------
+                generator.template = template.clone();
+                generator.template.prompt()?;
                 // [init git repository] -------------------------------------------------------------
                 generator.git = self.init_git();
                 if self.confirm_create() {
@@ -80,13 +139,20 @@ impl CreateArgs {
                     generator.generate()
                 } else {
                     CreateLogs::Cancel.warning(lang).print();
-                    return self.create_project(lang);
+                    return self.create_project(lang, template);
                 }
             }
             Err(e) => Err(e.to_string().into()),
         }
     }
 
+    fn create_member(&self, lang: Language, template: &Template) -> Result<(), Error> {
+        let path = self.path.canonicalize().map_err(|e| Error::from(e.to_string()))?;
+        let mut template = template.clone();
+        template.prompt()?;
+        member::add(path.as_path(), &template, lang)
+    }
+
     fn init_git(&self) -> bool {
         Confirm::new("Init as a git repository?")
             .with_default(true)
@@ -0,0 +1,75 @@
+use std::{env::current_dir, path::Path, process::exit};
+
+use clap::Args;
+use gen_utils::{
+    common::{fs, ToToml},
+    error::Error,
+};
+
+use crate::{
+    entry::{ConfOverrides, FrameworkType, GenUIConf, Language, RactToml},
+    log::{InfoLogs, LogExt},
+};
+
+#[derive(Args, Debug)]
+pub struct InfoArgs {
+    /// the same flags as `ract run` and `ract build`, to preview how they override the config
+    #[command(flatten)]
+    pub overrides: ConfOverrides,
+}
+
+impl InfoArgs {
+    /// ## show the effective compiler config of each member and where each value comes from
+    pub fn run(&self, lang: Language) {
+        InfoLogs::Desc.info(lang).multi().print();
+        let path = current_dir().unwrap();
+        match self.info(path.as_path(), lang) {
+            Ok(_) => {
+                InfoLogs::Complete.success(lang).print();
+            }
+            Err(e) => {
+                InfoLogs::Error(e.to_string()).error(lang).print();
+                exit(2);
+            }
+        }
+    }
+
+    fn info(&self, path: &Path, lang: Language) -> Result<(), Error> {
+        let ract_toml: RactToml = (&RactToml::read(path.join(".ract"))?).try_into()?;
+        if let FrameworkType::Makepad = ract_toml.target {
+            return Err(Error::from("`ract info` only supports GenUI projects"));
+        }
+        let compiles = ract_toml
+            .compiles()
+            .ok_or_else(|| Error::from("can not get compile members from .ract"))?;
+
+        for member in compiles {
+            // the same layers as `Compiler::new` and `Compiler::overrides`
+            let mut conf = GenUIConf::new(path.join(member.source.as_path()))?;
+            conf.compiler.apply_env(|key| std::env::var(key).ok())?;
+            conf.compiler.apply_cli(&self.overrides)?;
+
+            InfoLogs::Member(fs::path_to_str(&member.source))
+                .info(lang)
+                .print();
+            let layered = conf.compiler.layered();
+            let width = layered
+                .iter()
+                .map(|(key, _, _)| key.len())
+                .max()
+                .unwrap_or(0);
+            for (key, value, layers) in layered {
+                let layers = layers
+                    .iter()
+                    .map(ToString::to_string)
+                    .collect::<Vec<String>>()
+                    .join(" + ");
+                println!("  {:<width$} = {}  # {}", key, value, layers, width = width);
+            }
+            for warning in conf.warnings.iter() {
+                InfoLogs::Warning(warning.to_string()).warning(lang).print();
+            }
+        }
+        Ok(())
+    }
+}
@@ -6,6 +6,9 @@ pub mod studio;
 pub mod wasm;
 pub mod package;
 pub mod run;
+pub mod build;
+pub mod snapshot;
+pub mod info;
 pub mod create;
 pub mod install;
 pub mod add;
\ No newline at end of file
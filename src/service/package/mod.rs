@@ -1,7 +1,7 @@
 mod info;
 mod works;
 
-use info::*;
+pub use info::PackageInfo;
 use std::{
     collections::HashMap,
     env::current_dir,
@@ -16,7 +16,7 @@ use crate::{
     log::PackageLogs,
 };
 use crate::{
-    entry::{Language, PackageFormat},
+    entry::{Language, PackageFormat, Resource},
     log::{LogExt, LogItem},
 };
 use cargo_metadata::MetadataCommand;
@@ -67,7 +67,7 @@ fn init_or_package(lang: Language) -> Result<(), Error> {
                         run_cargo_packager(info, lang)
                     }
                     "skip" => {
-                        let info = get_target_and_dist()?;
+                        let info = package_info()?;
                         run_cargo_packager(info, lang)
                     }
                     _ => Err("Invalid option".into()),
@@ -132,11 +132,25 @@ fn generate_packager_toml(lang: Language) -> Result<PackageInfo, Error> {
         )
     };
     // [get package configuration] ----------------------------------------------------------------
-    let mut conf = generate_package_conf(path.as_path(), framework.as_ref(), lang)?;
-    // [write to Cargo.toml] -----------------------------------------------------------------------
-    let generator = conf.generator(path.as_path(), framework);
-    let _ = generator.generate(&conf)?;
+    let conf = generate_package_conf(path.as_path(), framework.as_ref(), lang)?;
+    let info = generate(path, conf, framework, resources)?;
     PackageLogs::PackageResourced.success(lang).print();
+    Ok(info)
+}
+
+/// ## write the package configuration to the target project
+/// patch `[package.metadata.packager]` into Cargo.toml and generate the package resources
+pub fn generate<P>(
+    path: P,
+    mut conf: PackageConf,
+    framework: Option<FrameworkType>,
+    resources: Vec<Resource>,
+) -> Result<PackageInfo, Error>
+where
+    P: AsRef<Path>,
+{
+    let generator = conf.generator(path.as_ref(), framework);
+    generator.generate(&conf)?;
     Ok(PackageInfo::new(path, conf, framework, resources))
 }
 
@@ -235,6 +249,13 @@ fn run_cargo_packager(info: PackageInfo, lang: Language) -> Result<(), Error> {
     if !confirm {
         return Ok(());
     }
+    pack(info, lang)
+}
+
+/// ## package the project by cargo-packager
+/// copy resources, do platform works and run `cargo packager --release`.
+/// like `ract package`, paths are resolved from the current dir (the project root)
+pub fn pack(info: PackageInfo, lang: Language) -> Result<(), Error> {
     PackageLogs::Start.info(lang).print();
     let resources = info.zip_resources();
     let PackageInfo {
@@ -318,7 +339,9 @@ fn copy_resources(
     Ok(())
 }
 
-fn get_target_and_dist() -> Result<PackageInfo, Error> {
+/// ## read the package info of the project in the current dir
+/// the package configuration is read from the target project's Cargo.toml (generated by `ract package`)
+pub fn package_info() -> Result<PackageInfo, Error> {
     let ract_path = RactToml::path();
     // [get conf from target project Cargo.toml] ---------------------------------------------------
     let (target_path, framework, resources) = if !ract_path.exists() {
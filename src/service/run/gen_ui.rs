@@ -2,9 +2,11 @@ use std::path::Path;
 
 use gen_utils::{compiler::CompilerImpl, error::Error};
 
-use crate::entry::{Compiler, RactToml};
+use crate::entry::{Compiler, Language, RactToml};
 
-pub fn run<P>(path: P, ract_toml: &RactToml) -> Result<(), Error>
+use super::{runner::AppRunner, RunArgs};
+
+pub fn run<P>(path: P, ract_toml: &RactToml, args: &RunArgs, lang: Language) -> Result<(), Error>
 where
     P: AsRef<Path>,
 {
@@ -13,6 +15,30 @@ where
         let member = compiles[0];
         // [generate compiler service] -----------------------------------------------------------------------
         let mut compiler = Compiler::new(path.as_ref(), member)?;
+        compiler.prune = !args.no_prune;
+        compiler.strict = args.strict;
+        compiler.overrides(&args.overrides)?;
+        if let Some(metrics) = args.metrics.as_ref() {
+            compiler.metrics_output(path.as_ref().join(metrics));
+        }
+        if let Some(rpc) = args.rpc.as_ref() {
+            compiler.serve(rpc)?;
+        }
+        // [hot restart] -------------------------------------------------------------------------------------
+        // the compiled project is a member of the workspace, its package name is the dir name (`src_gen_0`)
+        if !args.no_launch {
+            let package = member
+                .target
+                .file_name()
+                .map(|name| name.to_string_lossy().to_string());
+            let runner = AppRunner::new(
+                path.as_ref(),
+                package,
+                compiler.conf.compiler.diagnostics,
+                lang,
+            );
+            compiler.on_updated(move || runner.restart());
+        }
 
         compiler.run();
 
@@ -1,11 +1,21 @@
 use std::{
     path::Path,
     process::{Command, Stdio},
+    sync::mpsc::channel,
 };
 
-use gen_utils::{common::stream_terminal, error::Error};
+use gen_utils::{
+    common::{fs, stream_terminal},
+    error::Error,
+};
+use notify::{EventKind, RecommendedWatcher, RecursiveMode, Watcher};
+
+use crate::{
+    entry::{CompilerConf, ConfOverrides, Excludes, Language},
+    log::{LogExt, LogItem, ProjectLogs},
+};
 
-use crate::{entry::Language, log::{LogExt, LogItem, ProjectLogs}};
+use super::runner::AppRunner;
 
 pub fn run<P>(path: P, lang: Language) -> Result<(), Error>
 where
@@ -38,3 +48,68 @@ where
         },
     )
 }
+
+/// ## run the project and restart it when `src/`, `resources/` or `Cargo.toml` changes
+/// excludes are gitignore-style (same as the GenUI compiler), `.gitignore` of the project is respected,
+/// `GENUI_EXCLUDES` and `--exclude` add more patterns
+pub fn watch<P>(path: P, overrides: &ConfOverrides, lang: Language) -> Result<(), Error>
+where
+    P: AsRef<Path>,
+{
+    let path = path.as_ref();
+    let mut conf = CompilerConf {
+        excludes: Excludes(
+            ["/target", "/Cargo.lock", "*.swp", "*~", ".#*", "4913"]
+                .into_iter()
+                .map(ToString::to_string)
+                .collect(),
+        ),
+        use_gitignore: true,
+        ..Default::default()
+    };
+    conf.apply_env(|key| std::env::var(key).ok())?;
+    conf.apply_cli(overrides)?;
+    let excludes = conf.excludes.matcher(path, conf.use_gitignore)?;
+    let manifest = path.join("Cargo.toml");
+    let dirs = [path.join("src"), path.join("resources")];
+    // [first run] -----------------------------------------------------------------------------------------
+    let runner = AppRunner::new(path, None, Default::default(), lang);
+    runner.restart();
+    // [watcher] -------------------------------------------------------------------------------------------
+    let (tx, rx) = channel();
+    let mut watcher = RecommendedWatcher::new(tx, Default::default()).map_err(|e| e.to_string())?;
+    // watch the project dir (not recursive) for `Cargo.toml`, editors may replace the file when saving
+    watcher
+        .watch(path, RecursiveMode::NonRecursive)
+        .map_err(|e| e.to_string())?;
+    for dir in dirs.iter().filter(|dir| dir.is_dir()) {
+        watcher
+            .watch(dir, RecursiveMode::Recursive)
+            .map_err(|e| e.to_string())?;
+    }
+    ProjectLogs::Watch(fs::path_to_str(path)).info(lang).print();
+
+    while let Ok(event) = rx.recv() {
+        match event {
+            Ok(event) => {
+                if !matches!(
+                    event.kind,
+                    EventKind::Create(_) | EventKind::Modify(_) | EventKind::Remove(_)
+                ) {
+                    continue;
+                }
+
+                let changed = event.paths.iter().any(|changed| {
+                    (changed == &manifest || dirs.iter().any(|dir| changed.starts_with(dir)))
+                        && !excludes.is_excluded(changed)
+                });
+                if changed {
+                    runner.restart();
+                }
+            }
+            Err(e) => LogItem::error(e.to_string()).print(),
+        }
+    }
+
+    Ok(())
+}
@@ -1,33 +1,71 @@
-use std::{env::current_dir, path::Path, process::exit};
+use std::{
+    env::current_dir,
+    path::{Path, PathBuf},
+    process::exit,
+};
 
+use clap::Args;
 use gen_utils::{common::ToToml, error::Error};
 
 use crate::{
-    entry::{Language, RactToml},
+    entry::{ConfOverrides, Language, RactToml},
     log::{LogExt, LogItem, ProjectLogs},
 };
 
 pub mod gen_ui;
 pub mod makepad;
+pub mod runner;
+
+#[derive(Args, Debug)]
+pub struct RunArgs {
+    /// Rebuild and restart the app when files change (GenUI projects are always watched)
+    #[arg(short, long, default_value = "false")]
+    pub watch: bool,
+    /// Write compile timings as json to the file after each compile pass (GenUI projects only)
+    #[arg(long)]
+    pub metrics: Option<PathBuf>,
+    /// Serve the compiler state by JSON-RPC on a localhost port or `unix:<socket>` (GenUI projects only)
+    #[arg(long, value_name = "ADDR")]
+    pub rpc: Option<String>,
+    /// Keep the outputs of sources which are deleted while ract is not running (GenUI projects only)
+    #[arg(long, default_value = "false")]
+    pub no_prune: bool,
+    /// Fail if gen_ui.toml has unknown keys, values of the wrong type or broken plugins (GenUI projects only)
+    #[arg(long, default_value = "false")]
+    pub strict: bool,
+    /// Only watch and compile, do not build and launch the app (GenUI projects only)
+    #[arg(long, default_value = "false")]
+    pub no_launch: bool,
+    #[command(flatten)]
+    pub overrides: ConfOverrides,
+}
 
-pub fn run(lang: Language) {
-    ProjectLogs::Desc.info(lang).multi().print();
-    // get current dir path and check has .ract file
-    let path = current_dir().unwrap();
-    if let Err(e) = run_project(path, lang) {
-        LogItem::error(e.to_string()).print();
-        exit(2);
+impl RunArgs {
+    pub fn run(&self, lang: Language) {
+        ProjectLogs::Desc.info(lang).multi().print();
+        // get current dir path and check has .ract file
+        let path = current_dir().unwrap();
+        if let Err(e) = run_project(path, self, lang) {
+            LogItem::error(e.to_string()).print();
+            exit(2);
+        }
     }
 }
 
-fn run_project<P>(path: P, lang: Language) -> Result<(), Error>
+fn run_project<P>(path: P, args: &RunArgs, lang: Language) -> Result<(), Error>
 where
     P: AsRef<Path>,
 {
     let ract_toml: RactToml = (&RactToml::read(path.as_ref().join(".ract"))?).try_into()?;
 
     match &ract_toml.target {
-        crate::entry::FrameworkType::GenUI => gen_ui::run(path.as_ref(), &ract_toml),
-        crate::entry::FrameworkType::Makepad => makepad::run(path.as_ref(), lang),
+        crate::entry::FrameworkType::GenUI => gen_ui::run(path.as_ref(), &ract_toml, args, lang),
+        crate::entry::FrameworkType::Makepad => {
+            if args.watch {
+                makepad::watch(path.as_ref(), &args.overrides, lang)
+            } else {
+                makepad::run(path.as_ref(), lang)
+            }
+        }
     }
 }
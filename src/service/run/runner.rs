@@ -0,0 +1,256 @@
+use std::{
+    io::{BufRead, BufReader, Read},
+    path::Path,
+    process::{Child, Command, Stdio},
+    sync::mpsc::{channel, RecvTimeoutError, Sender},
+    thread::{self, JoinHandle},
+    time::Duration,
+};
+
+use cargo_metadata::Message;
+use gen_utils::error::Error;
+
+use crate::{
+    entry::{DiagnosticFormat, Language, SourceMap},
+    log::{LogExt, LogItem, ProjectLogs},
+};
+
+/// rapid edits in this duration only trigger one rebuild
+const DEBOUNCE: Duration = Duration::from_millis(300);
+
+/// # App Runner
+/// build and (re)start the app in a background thread
+///
+/// each `restart` request is debounced, then the app is rebuilt by `cargo build`:
+/// - build success: the old app process (and its children) is killed and the new one is started
+/// - build failed: the errors are shown and the last good build keeps running
+///
+/// stdout and stderr of cargo and the app are streamed into current session (see [build] for diagnostics),
+/// the app is killed when the runner is dropped
+pub struct AppRunner {
+    sender: Option<Sender<()>>,
+    handle: Option<JoinHandle<()>>,
+}
+
+impl AppRunner {
+    /// ## new runner
+    /// - path: dir to run cargo (the project or its workspace)
+    /// - package: package to build and run, `None` means the package in `path`
+    /// - format: format of the diagnostics which are mapped to the source project
+    pub fn new<P>(
+        path: P,
+        package: Option<String>,
+        format: DiagnosticFormat,
+        lang: Language,
+    ) -> Self
+    where
+        P: AsRef<Path>,
+    {
+        let (sender, receiver) = channel::<()>();
+        let path = path.as_ref().to_path_buf();
+
+        let handle = thread::spawn(move || {
+            let mut app: Option<Child> = None;
+            while receiver.recv().is_ok() {
+                // [debounce] ------------------------------------------------------------------------------------
+                loop {
+                    match receiver.recv_timeout(DEBOUNCE) {
+                        Ok(_) => continue,
+                        Err(RecvTimeoutError::Timeout) => break,
+                        Err(RecvTimeoutError::Disconnected) => {
+                            stop(app.take());
+                            return;
+                        }
+                    }
+                }
+                // [rebuild] -------------------------------------------------------------------------------------
+                ProjectLogs::Rebuild.info(lang).print();
+                match build(path.as_path(), package.as_ref(), format, lang) {
+                    Ok(true) => {
+                        // [restart] -------------------------------------------------------------------------------
+                        let restart = app.is_some();
+                        stop(app.take());
+                        if restart {
+                            ProjectLogs::Restart.info(lang).print();
+                        } else {
+                            ProjectLogs::Start.info(lang).print();
+                        }
+                        match start(path.as_path(), package.as_ref()) {
+                            Ok(child) => app = Some(child),
+                            Err(e) => ProjectLogs::Error(e.to_string()).error(lang).print(),
+                        }
+                    }
+                    Ok(false) => ProjectLogs::BuildFailed.warning(lang).print(),
+                    Err(e) => ProjectLogs::Error(e.to_string()).error(lang).print(),
+                }
+            }
+            stop(app.take());
+        });
+
+        Self {
+            sender: Some(sender),
+            handle: Some(handle),
+        }
+    }
+    /// ## request to rebuild and restart the app
+    pub fn restart(&self) {
+        if let Some(sender) = self.sender.as_ref() {
+            let _ = sender.send(());
+        }
+    }
+}
+
+impl Drop for AppRunner {
+    fn drop(&mut self) {
+        // close the channel, the runner thread will stop the app and exit
+        self.sender.take();
+        if let Some(handle) = self.handle.take() {
+            let _ = handle.join();
+        }
+    }
+}
+
+fn cargo<P>(path: P, cmd: &str, package: Option<&String>) -> Command
+where
+    P: AsRef<Path>,
+{
+    let mut command = Command::new("cargo");
+    command.arg(cmd).current_dir(path);
+    if let Some(package) = package {
+        command.args(["-p", package]);
+    }
+    command
+}
+
+/// ## build the app, return the build is success or not
+/// cargo runs with `--message-format=json`, diagnostics in the compiled GenUI project (the package dir
+/// which has a [SourceMap]) are emitted against the source files, others are printed as cargo renders them.
+/// mapping is best-effort, if the source map can not be read all diagnostics are printed as they are
+pub fn build(
+    path: &Path,
+    package: Option<&String>,
+    format: DiagnosticFormat,
+    lang: Language,
+) -> Result<bool, Error> {
+    let source_map = package
+        .map_or_else(
+            || Ok(SourceMap::default()),
+            |package| SourceMap::read(path.join(package)),
+        )
+        .unwrap_or_else(|e| {
+            ProjectLogs::SourceMapFailed(e.to_string())
+                .warning(lang)
+                .print();
+            SourceMap::default()
+        });
+    let mut child = cargo(path, "build", package)
+        .arg("--message-format=json-diagnostic-rendered-ansi")
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .map_err(|e| e.to_string())?;
+
+    // progress of cargo
+    let stderr = child
+        .stderr
+        .take()
+        .map(|stderr| stream(stderr, |line| LogItem::warning(line).print()));
+    if let Some(stdout) = child.stdout.take() {
+        for message in Message::parse_stream(BufReader::new(stdout)).map_while(Result::ok) {
+            match message {
+                Message::CompilerMessage(message) => {
+                    match source_map.map_cargo(path, &message.message) {
+                        Some(diagnostic) => diagnostic.emit(format),
+                        None => {
+                            for line in message.message.rendered.unwrap_or_default().lines() {
+                                LogItem::warning(line.to_string()).print();
+                            }
+                        }
+                    }
+                }
+                Message::TextLine(line) => LogItem::info(line).print(),
+                _ => {}
+            }
+        }
+    }
+    if let Some(stderr) = stderr {
+        let _ = stderr.join();
+    }
+
+    child
+        .wait()
+        .map(|status| status.success())
+        .map_err(|e| e.to_string().into())
+}
+
+/// ## start the app, output is streamed in background
+fn start(path: &Path, package: Option<&String>) -> Result<Child, Error> {
+    let mut child = cargo(path, "run", package)
+        .arg("-q")
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .map_err(|e| e.to_string())?;
+
+    if let Some(stdout) = child.stdout.take() {
+        stream(stdout, |line| LogItem::info(line).print());
+    }
+    if let Some(stderr) = child.stderr.take() {
+        stream(stderr, |line| LogItem::warning(line).print());
+    }
+
+    Ok(child)
+}
+
+fn stream<R, F>(reader: R, f: F) -> JoinHandle<()>
+where
+    R: Read + Send + 'static,
+    F: Fn(String) + Send + 'static,
+{
+    thread::spawn(move || {
+        for line in BufReader::new(reader).lines().map_while(Result::ok) {
+            f(line);
+        }
+    })
+}
+
+/// ## stop the app with all its children
+/// the app is started by `cargo run`, so the real app is a child of cargo
+fn stop(app: Option<Child>) {
+    if let Some(mut app) = app {
+        kill_tree(app.id());
+        let _ = app.kill();
+        let _ = app.wait();
+    }
+}
+
+#[cfg(target_os = "windows")]
+fn kill_tree(pid: u32) {
+    let _ = Command::new("taskkill")
+        .args(["/T", "/F", "/PID", &pid.to_string()])
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .status();
+}
+
+/// kill children first (deepest first), so that no orphan process is left
+#[cfg(not(target_os = "windows"))]
+fn kill_tree(pid: u32) {
+    let children = Command::new("pgrep")
+        .args(["-P", &pid.to_string()])
+        .output()
+        .map(|output| {
+            String::from_utf8_lossy(&output.stdout)
+                .lines()
+                .filter_map(|line| line.trim().parse::<u32>().ok())
+                .collect::<Vec<u32>>()
+        })
+        .unwrap_or_default();
+
+    for child in children {
+        kill_tree(child);
+        let _ = Command::new("kill")
+            .args(["-TERM", &child.to_string()])
+            .status();
+    }
+}
@@ -0,0 +1,326 @@
+use std::{
+    cmp::Reverse,
+    env::current_dir,
+    path::{Path, PathBuf},
+    process::exit,
+};
+
+use clap::{Args, Subcommand};
+use gen_utils::{
+    common::{fs, ToToml},
+    error::Error,
+};
+use walkdir::WalkDir;
+
+use crate::{
+    common::TempDir,
+    entry::{ChainEnvToml, ConfOverrides, FrameworkType, Language, Member, RactToml, SOURCE_MAP},
+    log::{LogExt, SnapshotLogs},
+    service::build::{compile_in, diff},
+};
+
+/// dir of the snapshots in the workspace
+const SNAPSHOTS: &str = "snapshots";
+
+#[derive(Args, Debug)]
+pub struct SnapshotArgs {
+    #[command(subcommand)]
+    pub action: SnapshotAction,
+    #[command(flatten)]
+    pub overrides: ConfOverrides,
+}
+
+#[derive(Subcommand, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SnapshotAction {
+    /// Compile each member and store the normalized output under `snapshots/`
+    Record,
+    /// Compile each member and compare the normalized output with `snapshots/`, exit with `1` if they differ
+    Check,
+}
+
+impl SnapshotArgs {
+    /// ## record or check the snapshots of the GenUI project
+    /// exit with `1` if `check` finds differences, `2` if failed
+    pub fn run(&self, lang: Language) {
+        SnapshotLogs::Desc.info(lang).multi().print();
+        let path = current_dir().unwrap();
+        match self.snapshot(path.as_path(), lang) {
+            Ok(true) => {
+                SnapshotLogs::Complete.success(lang).print();
+            }
+            Ok(false) => exit(1),
+            Err(e) => {
+                SnapshotLogs::Error(e.to_string()).error(lang).print();
+                exit(2);
+            }
+        }
+    }
+
+    /// return all snapshots match or not (always true for `record`)
+    fn snapshot(&self, path: &Path, lang: Language) -> Result<bool, Error> {
+        let ract_toml: RactToml = (&RactToml::read(path.join(".ract"))?).try_into()?;
+        if let FrameworkType::Makepad = ract_toml.target {
+            return Err(Error::from("`ract snapshot` only supports GenUI projects"));
+        }
+        let compiles = ract_toml
+            .compiles()
+            .ok_or_else(|| Error::from("can not get compile members from .ract"))?;
+
+        let mut matched = true;
+        for member in compiles {
+            // temporary workspace is removed when it is dropped
+            let temp = TempDir::new("ract_snapshot")?;
+            matched &= self.member(temp.path(), path, member, lang)?;
+        }
+        Ok(matched)
+    }
+
+    fn member(
+        &self,
+        temp: &Path,
+        path: &Path,
+        member: &Member,
+        lang: Language,
+    ) -> Result<bool, Error> {
+        let snapshot = path.join(SNAPSHOTS).join(member.source.as_path());
+        let name = fs::path_to_str(&member.source);
+        let roots = Roots::new(temp, path);
+
+        match self.action {
+            SnapshotAction::Record => {
+                SnapshotLogs::Record(name.to_string()).info(lang).print();
+                compile_in(temp, path, member, &self.overrides)?;
+                if snapshot.exists() {
+                    fs::delete_dir(snapshot.as_path())?;
+                }
+                let files = store(
+                    temp.join(member.target.as_path()).as_path(),
+                    snapshot.as_path(),
+                    &roots,
+                )?;
+                SnapshotLogs::Recorded {
+                    snapshot: fs::path_to_str(snapshot.strip_prefix(path).unwrap_or(&snapshot)),
+                    files,
+                }
+                .success(lang)
+                .print();
+                Ok(true)
+            }
+            SnapshotAction::Check => {
+                SnapshotLogs::Check(name.to_string()).info(lang).print();
+                if !snapshot.exists() {
+                    return Err(Error::from(format!(
+                        "no snapshot of `{}`, use `ract snapshot record` first",
+                        name
+                    )));
+                }
+                compile_in(temp, path, member, &self.overrides)?;
+                let generated = temp.join(format!("{}.snapshot", name));
+                store(
+                    temp.join(member.target.as_path()).as_path(),
+                    generated.as_path(),
+                    &roots,
+                )?;
+
+                let changes = diff::compare(snapshot.as_path(), generated.as_path())?;
+                if changes.is_empty() {
+                    SnapshotLogs::Matched(name).success(lang).print();
+                    return Ok(true);
+                }
+                // [readable diff] ---------------------------------------------------------------------------------
+                for change in changes.iter() {
+                    println!("{}", change.summary());
+                    print!("{}", change.diff(snapshot.as_path(), generated.as_path())?);
+                }
+                let count = |f: fn(&diff::Change) -> bool| changes.iter().filter(|c| f(c)).count();
+                SnapshotLogs::Mismatched {
+                    member: name,
+                    added: count(|c| matches!(c, diff::Change::Added(_))),
+                    removed: count(|c| matches!(c, diff::Change::Removed(_))),
+                    changed: count(|c| matches!(c, diff::Change::Changed(_))),
+                }
+                .warning(lang)
+                .print();
+                Ok(false)
+            }
+        }
+    }
+}
+
+/// # Snapshot Roots
+/// machine specific paths and their placeholders, longer paths are replaced first
+#[derive(Debug, Clone, Default)]
+pub struct Roots(Vec<(String, String)>);
+
+impl Roots {
+    /// - temp: the temporary workspace where the member is compiled
+    /// - path: the workspace
+    ///
+    /// the chain dir and dependencies in `env.toml` are also replaced if the config can be read
+    pub fn new(temp: &Path, path: &Path) -> Self {
+        let mut roots = Self::default();
+        roots.push(temp, "{workspace}");
+        roots.push(path, "{workspace}");
+        if let Ok(chain_env_toml) = ChainEnvToml::path().and_then(ChainEnvToml::try_from) {
+            for (name, dep) in chain_env_toml.dependencies.iter() {
+                roots.push(dep, &format!("{{dep:{}}}", name));
+            }
+            roots.push(chain_env_toml.chain_path().as_path(), "{chain}");
+        }
+        roots
+    }
+    pub fn push<P>(&mut self, path: P, placeholder: &str)
+    where
+        P: AsRef<Path>,
+    {
+        let path = path.as_ref().to_string_lossy().to_string();
+        if path.is_empty() {
+            return;
+        }
+        // windows paths may be written with `/` or escaped `\\`
+        for path in [path.replace('\\', "/"), path.replace('\\', "\\\\"), path] {
+            if !self.0.iter().any(|(root, _)| *root == path) {
+                self.0.push((path, placeholder.to_string()));
+            }
+        }
+        self.0.sort_by_key(|(root, _)| Reverse(root.len()));
+    }
+}
+
+/// ## normalize the generated code so that snapshots are portable across machines
+/// - line endings are `\n`
+/// - paths in [Roots] are replaced by their placeholders, such as `{workspace}`
+/// - timestamps (`2025-01-31`, `2025-01-31 08:00:00`, `2025-01-31T08:00:00.123+08:00`) are replaced by `{timestamp}`,
+///   a timestamp in a word or a number (such as `v2025-01-31x` or `12025-01-31`) is kept
+pub fn normalize(content: &str, roots: &Roots) -> String {
+    let mut content = content.replace("\r\n", "\n");
+    for (root, placeholder) in roots.0.iter() {
+        content = content.replace(root.as_str(), placeholder);
+    }
+
+    let bytes = content.as_bytes();
+    let boundary = |index: usize| bytes.get(index).is_none_or(|b| !b.is_ascii_alphanumeric());
+    let mut res = String::with_capacity(content.len());
+    let (mut index, mut last) = (0, 0);
+    while index < bytes.len() {
+        let start = index == 0 || boundary(index - 1);
+        match timestamp_len(&bytes[index..]).filter(|len| start && boundary(index + len)) {
+            // timestamps start with an ascii digit, so `index` is always a char boundary
+            Some(len) => {
+                res.push_str(&content[last..index]);
+                res.push_str("{timestamp}");
+                index += len;
+                last = index;
+            }
+            None => index += 1,
+        }
+    }
+    res.push_str(&content[last..]);
+    res
+}
+
+/// length of the timestamp at the start of the bytes
+fn timestamp_len(bytes: &[u8]) -> Option<usize> {
+    let digits = |from: usize, len: usize| {
+        bytes
+            .get(from..from + len)
+            .is_some_and(|s| s.iter().all(u8::is_ascii_digit))
+    };
+    let is = |index: usize, c: u8| bytes.get(index) == Some(&c);
+
+    // [date] ----------------------------------------------------------------------------------------------------
+    if !(digits(0, 4) && is(4, b'-') && digits(5, 2) && is(7, b'-') && digits(8, 2)) {
+        return None;
+    }
+    let mut len = 10;
+    // [time] ----------------------------------------------------------------------------------------------------
+    if (is(10, b'T') || is(10, b' ')) && digits(11, 2) && is(13, b':') && digits(14, 2) {
+        len = 16;
+        if is(16, b':') && digits(17, 2) {
+            len = 19;
+            if is(19, b'.') && digits(20, 1) {
+                len = 20
+                    + bytes[20..]
+                        .iter()
+                        .take_while(|b| b.is_ascii_digit())
+                        .count();
+            }
+        }
+        // [zone] ------------------------------------------------------------------------------------------------
+        if is(len, b'Z') {
+            len += 1;
+        } else if (is(len, b'+') || is(len, b'-')) && digits(len + 1, 2) {
+            if is(len + 3, b':') && digits(len + 4, 2) {
+                len += 6;
+            } else if digits(len + 3, 2) {
+                len += 5;
+            }
+        }
+    }
+    Some(len)
+}
+
+/// ## store the normalized copy of the compiled project
+/// `target`, `Cargo.lock` and the source map are skipped, binary files are copied as they are.
+/// return the number of stored files
+fn store(compiled: &Path, to: &Path, roots: &Roots) -> Result<usize, Error> {
+    let ignores = [
+        compiled.join("target"),
+        compiled.join("Cargo.lock"),
+        compiled.join(SOURCE_MAP),
+    ];
+    let files = WalkDir::new(compiled)
+        .into_iter()
+        .filter_entry(|e| !ignores.iter().any(|ignore| e.path() == ignore))
+        .filter_map(|e| e.ok())
+        .filter(|e| e.file_type().is_file())
+        .map(|e| e.path().to_path_buf())
+        .collect::<Vec<PathBuf>>();
+
+    for file in files.iter() {
+        let to = to.join(file.strip_prefix(compiled).map_err(|e| e.to_string())?);
+        let content = std::fs::read(file.as_path()).map_err(|e| e.to_string())?;
+        let content = match String::from_utf8(content) {
+            Ok(text) => normalize(&text, roots).into_bytes(),
+            Err(e) => e.into_bytes(),
+        };
+        if let Some(parent) = to.parent() {
+            std::fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+        }
+        std::fs::write(to.as_path(), content).map_err(|e| e.to_string())?;
+    }
+    Ok(files.len())
+}
+
+#[cfg(test)]
+mod test_snapshot {
+    use std::path::Path;
+
+    use super::{normalize, Roots};
+
+    #[test]
+    fn normalize_content() {
+        let mut roots = Roots::default();
+        roots.push(Path::new("/tmp/ract_snapshot_42"), "{workspace}");
+        roots.push(Path::new("/home/me/hello"), "{workspace}");
+        roots.push(Path::new("/home/me/.ract/chain"), "{chain}");
+        roots.push(
+            Path::new("/home/me/.ract/chain/makepad/widgets"),
+            "{dep:makepad-widgets}",
+        );
+
+        let content = "// generated at 2025-01-31 08:00:00\r\n\
+            // at 2025-01-31T08:00:00.123+08:00, 2025-01-31T08:00Z and 2025-01-31\r\n\
+            makepad-widgets = { path = \"/home/me/.ract/chain/makepad/widgets\" }\n\
+            include!(\"/tmp/ract_snapshot_42/src_gen_0/src/a.rs\"); // 2025-1-31, 20250131\n\
+            // 12025-01-31, v2025-01-31x, 2025-01-31T08:00:00x (2025-01-31)\n";
+        assert_eq!(
+            normalize(content, &roots),
+            "// generated at {timestamp}\n\
+            // at {timestamp}, {timestamp} and {timestamp}\n\
+            makepad-widgets = { path = \"{dep:makepad-widgets}\" }\n\
+            include!(\"{workspace}/src_gen_0/src/a.rs\"); // 2025-1-31, 20250131\n\
+            // 12025-01-31, v2025-01-31x, 2025-01-31T08:00:00x ({timestamp})\n"
+        );
+    }
+}